@@ -1,6 +1,8 @@
-use image::{GenericImageView, Rgba};
+use image::imageops::{self, FilterType};
+use image::{GenericImageView, Rgba, RgbaImage};
 use rayon::prelude::*;
 use crate::{MonochoraError, Result};
+use serde::{Deserialize, Serialize};
 
 static SIMPLE_CHARS: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
 static DETAILED_CHARS: &[char] = &[
@@ -9,9 +11,216 @@ static DETAILED_CHARS: &[char] = &[
     'c', 'z', 'X', 'Y', 'U', 'J', 'C', 'L', 'Q', '0', 'O', 'Z', 'm', 'w', 'q', 'p', 'd', 'b', 'k',
     'h', 'a', 'o', '*', '#', 'M', 'W', '&', '8', '%', 'B', '@'
 ];
+// Cells of the --debug-brightness grid: adjusted brightness from 0 (dark) to 9 (light).
+pub static BRIGHTNESS_DIGITS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+// Private-use character emitted for fully transparent pixels when `mark_transparent` is set,
+// so GIF output can tell them apart from dark opaque pixels that map to a space.
+pub const TRANSPARENT_CELL: char = '\u{E000}';
+
+// How far a cell's luminance standard deviation (at most 0.5) pushes it up the ramp in detail-aware mode.
+const DETAIL_WEIGHT: f32 = 0.5;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResampleFilter {
+    #[default]
+    Nearest,
+    Triangle,
+    Lanczos,
+}
+
+impl ResampleFilter {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "nearest" => Ok(Self::Nearest),
+            "triangle" | "bilinear" => Ok(Self::Triangle),
+            "lanczos" | "lanczos3" => Ok(Self::Lanczos),
+            _ => Err(MonochoraError::Config(format!(
+                "Invalid resample filter '{}', expected nearest, triangle or lanczos", name
+            ))),
+        }
+    }
+
+    fn filter_type(self) -> Option<FilterType> {
+        match self {
+            Self::Nearest => None,
+            Self::Triangle => Some(FilterType::Triangle),
+            Self::Lanczos => Some(FilterType::Lanczos3),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+// Standard xterm values for the 16 basic colors.
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+impl ColorDepth {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(Self::TrueColor),
+            "256" | "ansi256" => Ok(Self::Ansi256),
+            "16" | "ansi16" => Ok(Self::Ansi16),
+            _ => Err(MonochoraError::Config(format!(
+                "Invalid color depth '{}', expected truecolor, 256 or 16", name
+            ))),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256 colors",
+            Self::Ansi16 => "16 colors",
+        }
+    }
+
+    // Palette index for the indexed depths; None for truecolor.
+    fn palette_index(self, r: u8, g: u8, b: u8) -> Option<u8> {
+        match self {
+            Self::TrueColor => None,
+            Self::Ansi256 => Some(ansi256_index(r, g, b)),
+            Self::Ansi16 => Some(ansi16_index(r, g, b)),
+        }
+    }
+
+    fn indexed_escape(self, index: u8) -> String {
+        match self {
+            Self::Ansi16 if index < 8 => format!("\x1b[{}m", 30 + index),
+            Self::Ansi16 => format!("\x1b[{}m", 90 + index - 8),
+            _ => format!("\x1b[38;5;{}m", index),
+        }
+    }
+}
 
 #[repr(C)]
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Grayscale,
+}
+
+// Evenly spaced stops sampled from the matplotlib colormaps.
+const VIRIDIS_STOPS: [(u8, u8, u8); 9] = [
+    (68, 1, 84), (71, 44, 122), (59, 81, 139), (44, 113, 142), (33, 144, 141),
+    (39, 173, 129), (92, 200, 99), (170, 220, 50), (253, 231, 37),
+];
+const MAGMA_STOPS: [(u8, u8, u8); 9] = [
+    (0, 0, 4), (28, 16, 68), (79, 18, 123), (129, 37, 129), (181, 54, 122),
+    (229, 80, 100), (251, 135, 97), (254, 194, 135), (252, 253, 191),
+];
+const GRAYSCALE_STOPS: [(u8, u8, u8); 2] = [(0, 0, 0), (255, 255, 255)];
+
+impl Colormap {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "viridis" => Ok(Self::Viridis),
+            "magma" => Ok(Self::Magma),
+            "grayscale" | "greyscale" | "gray" | "grey" => Ok(Self::Grayscale),
+            _ => Err(MonochoraError::Config(format!(
+                "Invalid colormap '{}', expected viridis, magma or grayscale", name
+            ))),
+        }
+    }
+
+    fn stops(self) -> &'static [(u8, u8, u8)] {
+        match self {
+            Self::Viridis => &VIRIDIS_STOPS,
+            Self::Magma => &MAGMA_STOPS,
+            Self::Grayscale => &GRAYSCALE_STOPS,
+        }
+    }
+
+    // Linearly interpolates between the two stops around a 0.0-1.0 value.
+    pub fn color(self, value: f32) -> (u8, u8, u8) {
+        let stops = self.stops();
+        let position = value.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+        let t = position - index as f32;
+
+        let (r0, g0, b0) = stops[index];
+        let (r1, g1, b1) = stops[index + 1];
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        (lerp(r0, r1), lerp(g0, g1), lerp(b0, b1))
+    }
+}
+
+// Inverse of the palette mapping, so indexed escapes can be rendered back to pixels.
+pub(crate) fn ansi_palette_color(index: u8) -> (u8, u8, u8) {
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => ANSI16_PALETTE[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn ansi256_index(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            _ => 232 + ((r as u16 - 8) * 24 / 241) as u8,
+        };
+    }
+
+    let level = |v: u8| -> u8 {
+        match v {
+            0..=47 => 0,
+            48..=114 => 1,
+            _ => (v - 35) / 40,
+        }
+    };
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+fn ansi16_index(r: u8, g: u8, b: u8) -> u8 {
+    let distance = |&(pr, pg, pb): &(u8, u8, u8)| {
+        let dr = r as i32 - pr as i32;
+        let dg = g as i32 - pg as i32;
+        let db = b as i32 - pb as i32;
+        dr * dr + dg * dg + db * db
+    };
+
+    ANSI16_PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, color)| distance(color))
+        .map(|(index, _)| index as u8)
+        .unwrap_or(7)
+}
+
+#[repr(C)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AsciiConverterConfig {
     pub width: Option<u32>,        
     pub height: Option<u32>,       
@@ -20,7 +229,26 @@ pub struct AsciiConverterConfig {
     pub detailed: bool,
     pub preserve_aspect_ratio: bool, 
     pub scale_factor: Option<f32>,
+    pub scale_x: Option<f32>,
+    pub scale_y: Option<f32>,
     pub custom_charset: Option<Vec<char>>,
+    pub brightness: f32,
+    pub contrast: f32,
+    pub invert_colors: bool,
+    pub channel_order: Option<[usize; 3]>,
+    pub posterize_levels: Option<u32>,
+    pub color_merge_threshold: u8,
+    pub resample: ResampleFilter,
+    pub color_depth: ColorDepth,
+    pub colormap: Option<Colormap>,
+    pub mark_transparent: bool,
+    pub sample_offset: [f32; 2],
+    pub sample_jitter: f32,
+    pub sample_seed: u64,
+    pub area_luminance: bool,
+    pub min_brightness: f32,
+    pub max_brightness: f32,
+    pub detail_aware: bool,
 }
 
 impl Default for AsciiConverterConfig {
@@ -33,32 +261,97 @@ impl Default for AsciiConverterConfig {
             detailed: true,
             preserve_aspect_ratio: true, 
             scale_factor: None,
+            scale_x: None,
+            scale_y: None,
             custom_charset: None,
+            brightness: 0.0,
+            contrast: 1.0,
+            invert_colors: false,
+            channel_order: None,
+            posterize_levels: None,
+            color_merge_threshold: 0,
+            resample: ResampleFilter::Nearest,
+            color_depth: ColorDepth::TrueColor,
+            colormap: None,
+            mark_transparent: false,
+            sample_offset: [0.0, 0.0],
+            sample_jitter: 0.0,
+            sample_seed: 0,
+            area_luminance: false,
+            min_brightness: 0.0,
+            max_brightness: 1.0,
+            detail_aware: false,
         }
     }
 }
 
 impl AsciiConverterConfig {
     pub fn validate(&self) -> Result<()> {
-        if let Some(width) = self.width {
-            if width == 0 {
-                return Err(MonochoraError::InvalidDimensions { width, height: self.height.unwrap_or(0) });
-            }
+        if let Some(width) = self.width
+            && width == 0 {
+            return Err(MonochoraError::InvalidDimensions { width, height: self.height.unwrap_or(0) });
         }
         
-        if let Some(height) = self.height {
-            if height == 0 {
-                return Err(MonochoraError::InvalidDimensions { width: self.width.unwrap_or(0), height });
-            }
+        if let Some(height) = self.height
+            && height == 0 {
+            return Err(MonochoraError::InvalidDimensions { width: self.width.unwrap_or(0), height });
         }
         
         if self.char_aspect <= 0.0 {
             return Err(MonochoraError::Config("Character aspect ratio must be positive".to_string()));
         }
         
-        if let Some(scale) = self.scale_factor {
-            if scale <= 0.0 {
-                return Err(MonochoraError::Config("Scale factor must be positive".to_string()));
+        if let Some(scale) = self.scale_factor
+            && scale <= 0.0 {
+            return Err(MonochoraError::Config("Scale factor must be positive".to_string()));
+        }
+
+        if self.scale_x.is_some_and(|scale| scale <= 0.0) || self.scale_y.is_some_and(|scale| scale <= 0.0) {
+            return Err(MonochoraError::Config("Per-axis scale factors must be positive".to_string()));
+        }
+
+        if self.scale_factor.is_some() && (self.scale_x.is_some() || self.scale_y.is_some()) {
+            return Err(MonochoraError::Config("Scale factor cannot be combined with per-axis scale factors".to_string()));
+        }
+        
+        if !(-1.0..=1.0).contains(&self.brightness) {
+            return Err(MonochoraError::Config("Brightness adjustment must be between -1.0 and 1.0".to_string()));
+        }
+
+        if self.contrast <= 0.0 {
+            return Err(MonochoraError::Config("Contrast must be positive".to_string()));
+        }
+
+        if !(0.0..=1.0).contains(&self.min_brightness) || !(0.0..=1.0).contains(&self.max_brightness)
+            || self.min_brightness >= self.max_brightness {
+            return Err(MonochoraError::Config(format!(
+                "Brightness clamp must satisfy 0.0 <= min < max <= 1.0 (got {} and {})", self.min_brightness, self.max_brightness
+            )));
+        }
+
+        if let Some(levels) = self.posterize_levels
+            && levels < 2 {
+            return Err(MonochoraError::Config("Posterize levels must be at least 2".to_string()));
+        }
+
+        if self.sample_offset.iter().any(|offset| !(0.0..1.0).contains(offset)) {
+            return Err(MonochoraError::Config("Sample offset must be between 0.0 and 1.0 (exclusive) of a cell".to_string()));
+        }
+
+        if !(0.0..=1.0).contains(&self.sample_jitter) {
+            return Err(MonochoraError::Config("Sample jitter must be between 0.0 and 1.0 cells".to_string()));
+        }
+
+        // A resampled grid is read one pixel per cell, so there is no sampling position to move.
+        if self.resample != ResampleFilter::Nearest && (self.sample_jitter > 0.0 || self.sample_offset != [0.0, 0.0]) {
+            return Err(MonochoraError::Config("Sample offset and jitter only apply to nearest-neighbor sampling".to_string()));
+        }
+
+        if let Some(order) = self.channel_order {
+            let mut sorted = order;
+            sorted.sort_unstable();
+            if sorted != [0, 1, 2] {
+                return Err(MonochoraError::Config("Channel order must be a permutation of RGB".to_string()));
             }
         }
         
@@ -74,7 +367,57 @@ impl AsciiConverterConfig {
         Ok(())
     }
 
-    fn get_charset(&self) -> &[char] {
+    fn transform_pixel(&self, r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+        let (r, g, b) = match self.channel_order {
+            Some(order) => {
+                let channels = [r, g, b];
+                (channels[order[0]], channels[order[1]], channels[order[2]])
+            }
+            None => (r, g, b),
+        };
+
+        if self.invert_colors {
+            (255 - r, 255 - g, 255 - b)
+        } else {
+            (r, g, b)
+        }
+    }
+
+    fn adjust_brightness(&self, brightness: f32) -> f32 {
+        let adjusted = ((brightness - 0.5) * self.contrast + 0.5 + self.brightness).clamp(0.0, 1.0);
+        let adjusted = match self.posterize_levels {
+            Some(levels) if levels >= 2 => {
+                let steps = (levels - 1) as f32;
+                (adjusted * steps).round() / steps
+            }
+            _ => adjusted,
+        };
+        let adjusted = if self.invert { 1.0 - adjusted } else { adjusted };
+        // A hard clamp on the final value, so only part of the ramp is ever used.
+        adjusted.clamp(self.min_brightness, self.max_brightness)
+    }
+
+    // --detail-aware: busy cells move toward denser characters by half their luminance standard
+    // deviation (at most a quarter of the ramp), so texture survives where the mean alone is flat.
+    fn detail_brightness(&self, brightness: f32, stats: Option<(f32, f32)>) -> f32 {
+        match stats {
+            Some((_, variance)) if self.detail_aware => {
+                (brightness + DETAIL_WEIGHT * variance.sqrt()).clamp(self.min_brightness, self.max_brightness)
+            }
+            _ => brightness,
+        }
+    }
+
+    // Character grid (columns, rows) that converting an image of this size would produce.
+    pub fn target_dimensions(&self, img_width: u32, img_height: u32) -> Result<(u32, u32)> {
+        calculate_target_dimensions(img_width, img_height, self)
+    }
+
+    fn transparent_char(&self) -> char {
+        if self.mark_transparent { TRANSPARENT_CELL } else { ' ' }
+    }
+
+    pub fn get_charset(&self) -> &[char] {
         if let Some(custom) = &self.custom_charset {
             custom.as_slice()
         } else if self.detailed {
@@ -98,40 +441,40 @@ where
         return Err(MonochoraError::InvalidDimensions { width: img_width, height: img_height });
     }
     
-    let (target_width, target_height) = calculate_target_dimensions(
-        img_width, 
-        img_height, 
-        config
-    )?;
+    let (target_width, target_height) = config.target_dimensions(img_width, img_height)?;
     
     if target_width == 0 || target_height == 0 {
         return Err(MonochoraError::InvalidDimensions { width: target_width, height: target_height });
     }
     
+    let resized = config.resample.filter_type()
+        .map(|filter| imageops::resize(image, target_width, target_height, filter));
+    
     let result: Result<Vec<String>> = (0..target_height)
         .into_par_iter()
         .map(|y| {
             let mut line = String::with_capacity(target_width as usize);
             
             for x in 0..target_width {
-                let img_x = ((x as f64 / target_width as f64) * img_width as f64) as u32;
-                let img_y = ((y as f64 / target_height as f64) * img_height as f64) as u32;
-                
-                let img_x = img_x.min(img_width.saturating_sub(1));
-                let img_y = img_y.min(img_height.saturating_sub(1));
-                
-                let pixel = image.get_pixel(img_x, img_y);
-                let [r, g, b, a] = pixel.0;
+                let [r, g, b, a] = sample_cell(image, resized.as_ref(), x, y, target_width, target_height, config).0;
                 
                 if a == 0 {
-                    line.push(' ');
+                    line.push(config.transparent_char());
                     continue;
                 }
                 
-                let brightness = calculate_brightness(r, g, b);
-                let brightness = if config.invert { 1.0 - brightness } else { brightness };
+                let (r, g, b) = config.transform_pixel(r, g, b);
+                
+                let stats = (config.area_luminance || config.detail_aware)
+                    .then(|| cell_luminance_stats(image, x, y, target_width, target_height, config))
+                    .flatten();
+                let luminance = match stats {
+                    Some((mean, _)) if config.area_luminance => mean,
+                    _ => calculate_brightness(r, g, b),
+                };
+                let brightness = config.adjust_brightness(luminance);
                 
-                let char_index = calculate_char_index(brightness, chars.len());
+                let char_index = calculate_char_index(config.detail_brightness(brightness, stats), chars.len());
                 let ascii_char = chars.get(char_index)
                     .copied()
                     .unwrap_or(' '); 
@@ -146,6 +489,18 @@ where
     result
 }
 
+// Calibration view: the same sampling and brightness adjustments as image_to_ascii, printed as digits.
+pub fn image_to_brightness_grid<I>(image: &I, config: &AsciiConverterConfig) -> Result<Vec<String>>
+where
+    I: GenericImageView<Pixel = Rgba<u8>> + Sync,
+{
+    let digits = AsciiConverterConfig {
+        custom_charset: Some(BRIGHTNESS_DIGITS.to_vec()),
+        ..config.clone()
+    };
+    image_to_ascii(image, &digits)
+}
+
 pub fn image_to_colored_ascii<I>(image: &I, config: &AsciiConverterConfig) -> Result<Vec<String>>
 where
     I: GenericImageView<Pixel = Rgba<u8>> + Sync,
@@ -159,45 +514,66 @@ where
         return Err(MonochoraError::InvalidDimensions { width: img_width, height: img_height });
     }
     
-    let (target_width, target_height) = calculate_target_dimensions(
-        img_width, 
-        img_height, 
-        config
-    )?;
+    let (target_width, target_height) = config.target_dimensions(img_width, img_height)?;
     
     if target_width == 0 || target_height == 0 {
         return Err(MonochoraError::InvalidDimensions { width: target_width, height: target_height });
     }
     
+    let resized = config.resample.filter_type()
+        .map(|filter| imageops::resize(image, target_width, target_height, filter));
+    
     let result: Result<Vec<String>> = (0..target_height)
         .into_par_iter()
         .map(|y| {
             let mut line = String::new();
+            let mut run_color: Option<(u8, u8, u8)> = None;
+            let mut run_index: Option<u8> = None;
             
             for x in 0..target_width {
-                let img_x = ((x as f64 / target_width as f64) * img_width as f64) as u32;
-                let img_y = ((y as f64 / target_height as f64) * img_height as f64) as u32;
-                
-                let img_x = img_x.min(img_width.saturating_sub(1));
-                let img_y = img_y.min(img_height.saturating_sub(1));
-                
-                let pixel = image.get_pixel(img_x, img_y);
-                let [r, g, b, a] = pixel.0;
+                let [r, g, b, a] = sample_cell(image, resized.as_ref(), x, y, target_width, target_height, config).0;
                 
                 if a == 0 {
-                    line.push(' ');
+                    line.push(config.transparent_char());
                     continue;
                 }
                 
-                let brightness = calculate_brightness(r, g, b);
-                let brightness = if config.invert { 1.0 - brightness } else { brightness };
+                let (r, g, b) = config.transform_pixel(r, g, b);
                 
-                let char_index = calculate_char_index(brightness, chars.len());
+                let stats = (config.area_luminance || config.detail_aware)
+                    .then(|| cell_luminance_stats(image, x, y, target_width, target_height, config))
+                    .flatten();
+                let luminance = match stats {
+                    Some((mean, _)) if config.area_luminance => mean,
+                    _ => calculate_brightness(r, g, b),
+                };
+                let brightness = config.adjust_brightness(luminance);
+                
+                let char_index = calculate_char_index(config.detail_brightness(brightness, stats), chars.len());
                 let ascii_char = chars.get(char_index)
                     .copied()
                     .unwrap_or(' '); 
                 
-                line.push_str(&format!("\x1b[38;2;{};{};{}m{}", r, g, b, ascii_char));
+                let (r, g, b) = match config.colormap {
+                    Some(colormap) => colormap.color(brightness),
+                    None => (r, g, b),
+                };
+                
+                if let Some(index) = config.color_depth.palette_index(r, g, b) {
+                    if run_index != Some(index) {
+                        line.push_str(&config.color_depth.indexed_escape(index));
+                        run_index = Some(index);
+                    }
+                } else {
+                    // Neighbors close to the current run color reuse its escape instead of emitting a new one.
+                    let continues_run = run_color
+                        .is_some_and(|run| colors_within(run, (r, g, b), config.color_merge_threshold));
+                    if !continues_run {
+                        line.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+                        run_color = Some((r, g, b));
+                    }
+                }
+                line.push(ascii_char);
             }
             
             line.push_str("\x1b[0m");
@@ -208,6 +584,145 @@ where
     result
 }
 
+// Mean luminance (0.0-1.0) of the opaque pixels after color remapping, before brightness adjustments.
+pub fn average_brightness<I>(image: &I, config: &AsciiConverterConfig) -> Option<f32>
+where
+    I: GenericImageView<Pixel = Rgba<u8>>,
+{
+    let mut total = 0.0f64;
+    let mut count = 0u64;
+
+    for (_, _, pixel) in image.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a == 0 {
+            continue;
+        }
+
+        let (r, g, b) = config.transform_pixel(r, g, b);
+        total += calculate_brightness(r, g, b) as f64;
+        count += 1;
+    }
+
+    (count > 0).then(|| (total / count as f64) as f32)
+}
+
+pub fn parse_channel_order(spec: &str) -> Result<[usize; 3]> {
+    let invalid = || MonochoraError::Config(format!("Invalid channel mapping '{}', expected e.g. rgb->bgr", spec));
+
+    let spec = spec.to_lowercase();
+    let (source, target) = spec.split_once("->").unwrap_or(("rgb", spec.as_str()));
+    let source: Vec<char> = source.trim().chars().collect();
+    let target: Vec<char> = target.trim().chars().collect();
+
+    let is_permutation = |channels: &[char]| {
+        let mut sorted = channels.to_vec();
+        sorted.sort_unstable();
+        sorted == ['b', 'g', 'r']
+    };
+
+    if !is_permutation(&source) || !is_permutation(&target) {
+        return Err(invalid());
+    }
+
+    let mut order = [0usize; 3];
+    for (slot, channel) in target.iter().enumerate() {
+        order[slot] = source.iter().position(|c| c == channel).ok_or_else(invalid)?;
+    }
+
+    Ok(order)
+}
+
+// Nearest sampling reads the source pixel under the cell; resampled frames already match the grid.
+fn sample_cell<I>(
+    image: &I,
+    resized: Option<&RgbaImage>,
+    x: u32,
+    y: u32,
+    target_width: u32,
+    target_height: u32,
+    config: &AsciiConverterConfig,
+) -> Rgba<u8>
+where
+    I: GenericImageView<Pixel = Rgba<u8>>,
+{
+    if let Some(resized) = resized {
+        return *resized.get_pixel(x, y);
+    }
+
+    // Sampling position in cell units; the offset shifts the whole grid, the jitter each cell on its own.
+    let (jitter_x, jitter_y) = if config.sample_jitter > 0.0 {
+        let (dx, dy) = cell_jitter(config.sample_seed, x, y);
+        (dx * config.sample_jitter as f64, dy * config.sample_jitter as f64)
+    } else {
+        (0.0, 0.0)
+    };
+    let cell_x = (x as f64 + config.sample_offset[0] as f64 + jitter_x).max(0.0);
+    let cell_y = (y as f64 + config.sample_offset[1] as f64 + jitter_y).max(0.0);
+
+    let (img_width, img_height) = image.dimensions();
+    let img_x = ((cell_x / target_width as f64) * img_width as f64) as u32;
+    let img_y = ((cell_y / target_height as f64) * img_height as f64) as u32;
+
+    let img_x = img_x.min(img_width.saturating_sub(1));
+    let img_y = img_y.min(img_height.saturating_sub(1));
+
+    image.get_pixel(img_x, img_y)
+}
+
+// Mean and variance of the luminance of the opaque source pixels the cell covers. The mean lets thin
+// features the single sample misses still shift the character; the cell's color still comes from sample_cell.
+fn cell_luminance_stats<I>(image: &I, x: u32, y: u32, target_width: u32, target_height: u32, config: &AsciiConverterConfig) -> Option<(f32, f32)>
+where
+    I: GenericImageView<Pixel = Rgba<u8>>,
+{
+    let (img_width, img_height) = image.dimensions();
+    let span = |cell: u32, cells: u32, pixels: u32| {
+        let start = (cell as u64 * pixels as u64 / cells as u64) as u32;
+        let end = ((cell as u64 + 1) * pixels as u64 / cells as u64) as u32;
+        start.min(pixels - 1)..end.clamp(start + 1, pixels)
+    };
+
+    let mut total = 0.0f32;
+    let mut total_squares = 0.0f32;
+    let mut count = 0u32;
+    for img_y in span(y, target_height, img_height) {
+        for img_x in span(x, target_width, img_width) {
+            let [r, g, b, a] = image.get_pixel(img_x, img_y).0;
+            if a == 0 {
+                continue;
+            }
+            let (r, g, b) = config.transform_pixel(r, g, b);
+            let luminance = calculate_brightness(r, g, b);
+            total += luminance;
+            total_squares += luminance * luminance;
+            count += 1;
+        }
+    }
+
+    (count > 0).then(|| {
+        let mean = total / count as f32;
+        (mean, (total_squares / count as f32 - mean * mean).max(0.0))
+    })
+}
+
+// Deterministic per-cell displacement in [-1, 1) on each axis (splitmix64 over the seed and cell).
+fn cell_jitter(seed: u64, x: u32, y: u32) -> (f64, f64) {
+    let mut state = seed ^ (((x as u64) << 32) | y as u64);
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+    let unit = |bits: u64| (bits >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0;
+    (unit(next()), unit(next()))
+}
+
+fn colors_within(a: (u8, u8, u8), b: (u8, u8, u8), threshold: u8) -> bool {
+    a.0.abs_diff(b.0) <= threshold && a.1.abs_diff(b.1) <= threshold && a.2.abs_diff(b.2) <= threshold
+}
+
 fn calculate_brightness(r: u8, g: u8, b: u8) -> f32 {
     (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0
 }
@@ -239,6 +754,19 @@ fn calculate_target_dimensions(
         let scaled_height = (img_height as f32 * scale / config.char_aspect).max(1.0) as u32;
         return Ok((scaled_width, scaled_height));
     }
+
+    // An unset axis keeps its original size; height still gets the character aspect correction.
+    if config.scale_x.is_some() || config.scale_y.is_some() {
+        let scale_x = config.scale_x.unwrap_or(1.0);
+        let scale_y = config.scale_y.unwrap_or(1.0);
+        if scale_x <= 0.0 || scale_y <= 0.0 {
+            return Err(MonochoraError::Config("Per-axis scale factors must be positive".to_string()));
+        }
+
+        let scaled_width = (img_width as f32 * scale_x).max(1.0) as u32;
+        let scaled_height = (img_height as f32 * scale_y / config.char_aspect).max(1.0) as u32;
+        return Ok((scaled_width, scaled_height));
+    }
     
     if let (Some(width), Some(height)) = (config.width, config.height) {
         if width == 0 || height == 0 {
@@ -253,8 +781,7 @@ fn calculate_target_dimensions(
         }
         
         let height = if config.preserve_aspect_ratio {
-            let calculated_height = (width as f32 * img_height as f32 / img_width as f32 / config.char_aspect).max(1.0) as u32;
-            calculated_height
+            (width as f32 * img_height as f32 / img_width as f32 / config.char_aspect).max(1.0) as u32
         } else {
             (img_height as f32 / config.char_aspect).max(1.0) as u32
         };
@@ -267,8 +794,7 @@ fn calculate_target_dimensions(
         }
         
         let width = if config.preserve_aspect_ratio {
-            let calculated_width = (height as f32 * img_width as f32 / img_height as f32 * config.char_aspect).max(1.0) as u32;
-            calculated_width
+            (height as f32 * img_width as f32 / img_height as f32 * config.char_aspect).max(1.0) as u32
         } else {
             img_width
         };
@@ -284,3 +810,87 @@ fn calculate_target_dimensions(
     
     Ok((target_width, target_height))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn red_image() -> RgbaImage {
+        RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 255]))
+    }
+
+    #[test]
+    fn invert_colors_turns_red_into_cyan() {
+        let config = AsciiConverterConfig { invert_colors: true, ..AsciiConverterConfig::default() };
+        assert_eq!(config.transform_pixel(255, 0, 0), (0, 255, 255));
+
+        let config = AsciiConverterConfig { width: Some(2), height: Some(2), ..config };
+        let lines = image_to_colored_ascii(&red_image(), &config).unwrap();
+        assert!(lines[0].contains("38;2;0;255;255"), "{:?}", lines[0]);
+    }
+
+    #[test]
+    fn channel_order_parses_mappings_into_source_indices() {
+        assert_eq!(parse_channel_order("rgb->bgr").unwrap(), [2, 1, 0]);
+        assert_eq!(parse_channel_order("GBR").unwrap(), [1, 2, 0]);
+        assert_eq!(parse_channel_order("bgr->rgb").unwrap(), [2, 1, 0]);
+        assert!(parse_channel_order("rgb->rrb").is_err());
+        assert!(parse_channel_order("rgba").is_err());
+
+        let config = AsciiConverterConfig { channel_order: Some([2, 1, 0]), ..AsciiConverterConfig::default() };
+        assert_eq!(config.transform_pixel(255, 0, 0), (0, 0, 255));
+    }
+
+    #[test]
+    fn pure_red_maps_to_ansi_red() {
+        assert_eq!(ansi16_index(255, 0, 0), 9);
+        assert_eq!(ColorDepth::Ansi16.indexed_escape(9), "\x1b[91m");
+        assert_eq!(ansi16_index(205, 0, 0), 1);
+        assert_eq!(ColorDepth::Ansi16.indexed_escape(1), "\x1b[31m");
+
+        let config = AsciiConverterConfig {
+            width: Some(2),
+            height: Some(2),
+            color_depth: ColorDepth::Ansi16,
+            ..AsciiConverterConfig::default()
+        };
+        let lines = image_to_colored_ascii(&red_image(), &config).unwrap();
+        assert!(lines[0].starts_with("\x1b[91m"), "{:?}", lines[0]);
+        assert!(!lines[0].contains("38;2;"), "{:?}", lines[0]);
+    }
+
+    fn cell_for_shade(shade: u8, min_brightness: f32, max_brightness: f32) -> char {
+        let config = AsciiConverterConfig {
+            width: Some(1),
+            height: Some(1),
+            custom_charset: Some(vec!['0', '1', '2', '3', '4']),
+            min_brightness,
+            max_brightness,
+            ..AsciiConverterConfig::default()
+        };
+        let image = RgbaImage::from_pixel(2, 2, Rgba([shade, shade, shade, 255]));
+        image_to_ascii(&image, &config).unwrap()[0].chars().next().unwrap()
+    }
+
+    #[test]
+    fn brightness_clamp_keeps_extremes_off_the_ends_of_the_ramp() {
+        let (darkest, brightest) = (cell_for_shade(0, 0.0, 1.0), cell_for_shade(255, 0.0, 1.0));
+        assert_ne!(darkest, brightest);
+        assert!(['0', '4'].contains(&darkest) && ['0', '4'].contains(&brightest));
+
+        // Clamping either end to the middle pulls that extreme onto the middle character.
+        assert_eq!(cell_for_shade(0, 0.5, 1.0), '2');
+        assert_eq!(cell_for_shade(255, 0.0, 0.5), '2');
+        assert_eq!(cell_for_shade(255, 0.5, 1.0), brightest);
+        assert_eq!(cell_for_shade(0, 0.0, 0.5), darkest);
+    }
+
+    #[test]
+    fn brightness_clamp_rejects_an_empty_or_out_of_range_window() {
+        for (min_brightness, max_brightness) in [(0.5, 0.5), (0.8, 0.2), (-0.1, 1.0), (0.0, 1.5)] {
+            let config = AsciiConverterConfig { min_brightness, max_brightness, ..AsciiConverterConfig::default() };
+            assert!(config.validate().is_err(), "{} {}", min_brightness, max_brightness);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::converter::ColorDepth;
 use crate::{MonochoraError, Result};
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
@@ -6,12 +7,16 @@ use crossterm::{
     event::{poll, read, Event, KeyCode},
 };
 use rayon::prelude::*;
-use std::io::{self, Write};
-use std::time::Duration;
-use tokio::time::sleep;
-use tracing::{debug, warn};
+use std::io::{self, BufWriter, Write};
+use std::time::{Duration, Instant};
+use tokio::time::{sleep, sleep_until};
+use tracing::{debug, info, warn};
+use crate::handler::{default_loop_count, normalize_frame_delays, DEFAULT_FRAME_DELAY_MS};
+use crate::pipeline::PlaybackOptions;
+use crate::output::{calculate_line_character_count, frame_file_name, split_escapes, CaptionPosition};
+use crate::rle::{decode_rle_text, encode_rle_text, RLE_HEADER};
 use crate::terminal_watcher::{ResponsiveFrameManager, TerminalDimensions};
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
 
 pub fn get_terminal_size() -> Result<(u32, u32)> {
     let (cols, rows) = size()
@@ -24,6 +29,23 @@ pub fn get_terminal_size() -> Result<(u32, u32)> {
     Ok((cols as u32, rows as u32))
 }
 
+// Uses the conventional environment hints; terminals behind screen/tmux/SSH often lack COLORTERM.
+pub fn detect_color_depth() -> ColorDepth {
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default().to_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" || std::env::var_os("WT_SESSION").is_some() {
+        return ColorDepth::TrueColor;
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default().to_lowercase();
+    if term.contains("truecolor") || term.contains("direct") {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
 fn validate_animation_input(
     frames: &[Vec<String>],
     frame_delays: &[u16],
@@ -60,6 +82,15 @@ fn validate_animation_input(
     Ok(())
 }
 
+fn resolve_frame_delay(frame_delays: &[u16], frame_idx: usize) -> u16 {
+    let delay_ms = frame_delays.get(frame_idx)
+        .or_else(|| frame_delays.first())
+        .copied()
+        .unwrap_or(DEFAULT_FRAME_DELAY_MS);
+
+    if delay_ms == 0 { DEFAULT_FRAME_DELAY_MS } else { delay_ms }
+}
+
 pub async fn display_responsive_ascii_animation(
     frame_manager: &mut ResponsiveFrameManager,
     mut resize_rx: watch::Receiver<TerminalDimensions>,
@@ -83,7 +114,7 @@ pub async fn display_responsive_ascii_animation(
                         continue 'outer;
                     }
                 }
-                _ = sleep(Duration::from_millis(delays[frame_idx] as u64)) => {
+                _ = sleep(Duration::from_millis(resolve_frame_delay(&delays, frame_idx) as u64)) => {
                     execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
                     
                     for line in frame {
@@ -91,12 +122,11 @@ pub async fn display_responsive_ascii_animation(
                     }
                     stdout.flush()?;
 
-                    if poll(Duration::from_millis(0))? {
-                        if let Ok(Event::Key(key)) = read() {
-                            match key.code {
-                                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => break 'outer,
-                                _ => {}
-                            }
+                    if poll(Duration::from_millis(0))?
+                        && let Ok(Event::Key(key)) = read() {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => break 'outer,
+                            _ => {}
                         }
                     }
                 }
@@ -109,13 +139,211 @@ pub async fn display_responsive_ascii_animation(
     Ok(())
 }
 
+enum PlaybackInput {
+    Continue,
+    Quit,
+    Resumed,
+}
+
+fn draw_frame(stdout: &mut io::Stdout, frame: &[String], frame_idx: usize, clear_between_frames: bool) -> Result<()> {
+    if clear_between_frames {
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))
+            .map_err(|e| MonochoraError::Terminal(format!("Failed to clear screen: {}", e)))?;
+    } else {
+        execute!(stdout, MoveTo(0, 0))
+            .map_err(|e| MonochoraError::Terminal(format!("Failed to move cursor: {}", e)))?;
+    }
+    
+    for (line_idx, line) in frame.iter().enumerate() {
+        match writeln!(stdout, "{}", line) {
+            Ok(_) => {},
+            Err(e) => {
+                warn!("Failed to write line {} of frame {}: {}", line_idx, frame_idx, e);
+            }
+        }
+    }
+    
+    stdout.flush()
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to flush stdout: {}", e)))
+}
+
+fn poll_playback_input() -> PlaybackInput {
+    match poll(Duration::from_millis(0)) {
+        Ok(true) => {
+            match read() {
+                Ok(Event::Key(key)) => {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+                            debug!("User requested exit");
+                            PlaybackInput::Quit
+                        }
+                        KeyCode::Char('p') | KeyCode::Char('P') => {
+                            debug!("Animation paused, press any key to continue");
+                            match read() {
+                                Ok(_) => {
+                                    debug!("Animation resumed");
+                                    PlaybackInput::Resumed
+                                }
+                                Err(e) => {
+                                    warn!("Failed to read resume input: {}", e);
+                                    PlaybackInput::Continue
+                                }
+                            }
+                        }
+                        _ => PlaybackInput::Continue,
+                    }
+                }
+                Ok(_) => PlaybackInput::Continue,
+                Err(e) => {
+                    warn!("Failed to read terminal event: {}", e);
+                    PlaybackInput::Continue
+                }
+            }
+        }
+        Ok(false) => PlaybackInput::Continue,
+        Err(e) => {
+            warn!("Failed to poll for terminal events: {}", e);
+            PlaybackInput::Continue
+        }
+    }
+}
+
+// Plays frames as they arrive from a producer, then replays the collected frames for the remaining loops.
+// The frame count is only known once the stream ends, so a `loop_count` of None is resolved then, with the
+// same rule decoding uses.
+pub async fn display_streamed_ascii_animation(
+    mut frames: mpsc::Receiver<Result<(Vec<String>, u16)>>,
+    options: &PlaybackOptions,
+) -> Result<()> {
+    let clear_between_frames = options.clear_between_frames;
+    let mut stdout = io::stdout();
+    
+    execute!(stdout, Hide)
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to hide cursor: {}", e)))?;
+
+    if !clear_between_frames {
+        execute!(stdout, Clear(ClearType::All))
+            .map_err(|e| MonochoraError::Terminal(format!("Failed to clear screen: {}", e)))?;
+    }
+
+    let mut received_frames = Vec::new();
+    let mut frame_delays = Vec::new();
+    let mut frame_deadline = Instant::now();
+    let mut quit = false;
+
+    while let Some(next) = frames.recv().await {
+        let (frame, delay) = match next {
+            Ok(next) => next,
+            Err(e) => {
+                let _ = execute!(stdout, Show);
+                return Err(e);
+            }
+        };
+
+        // A frame that arrives late starts its own delay now rather than eating into it.
+        frame_deadline = frame_deadline.max(Instant::now());
+        frame_deadline += Duration::from_millis(if delay == 0 { DEFAULT_FRAME_DELAY_MS } else { delay } as u64);
+
+        draw_frame(&mut stdout, &frame, received_frames.len(), clear_between_frames)?;
+        received_frames.push(frame);
+        frame_delays.push(delay);
+
+        sleep_until(frame_deadline.into()).await;
+
+        match poll_playback_input() {
+            PlaybackInput::Quit => {
+                quit = true;
+                break;
+            }
+            PlaybackInput::Resumed => frame_deadline = Instant::now(),
+            PlaybackInput::Continue => {}
+        }
+    }
+
+    // Closing the channel tells the producer to stop decoding.
+    drop(frames);
+
+    let loop_count = options.loop_count.unwrap_or_else(|| default_loop_count(received_frames.len()));
+    if !quit && loop_count != 1 && !received_frames.is_empty() {
+        sleep(Duration::from_millis(50)).await;
+        let replay = PlaybackOptions { loop_count: Some(loop_count.saturating_sub(1)), ..*options };
+        return display_ascii_animation(&received_frames, &frame_delays, &replay).await;
+    }
+
+    execute!(stdout, Show)
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to show cursor: {}", e)))?;
+    
+    if options.clear_on_exit {
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))
+            .map_err(|e| MonochoraError::Terminal(format!("Failed to clear screen on exit: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameAction {
+    Draw,
+    DropLate,
+    SkipCapped,
+}
+
+// Each frame ends at a fixed wall-clock deadline, so time spent drawing doesn't stretch the animation.
+// Kept free of the clock itself: callers pass `now`, which lets the drift and drop decisions be tested.
+struct PlaybackSchedule {
+    deadline: Instant,
+    next_draw_at: Instant,
+    min_frame_interval: Option<Duration>,
+}
+
+impl PlaybackSchedule {
+    fn new(start: Instant, min_frame_interval: Option<Duration>) -> Self {
+        Self { deadline: start, next_draw_at: start, min_frame_interval }
+    }
+
+    // Late frames and frames inside the redraw budget are skipped, but their delay still runs.
+    fn next_frame(&mut self, delay_ms: u16, now: Instant) -> FrameAction {
+        self.deadline += Duration::from_millis(delay_ms as u64);
+
+        match self.min_frame_interval {
+            _ if now >= self.deadline => FrameAction::DropLate,
+            Some(_) if now < self.next_draw_at => FrameAction::SkipCapped,
+            Some(interval) => {
+                self.next_draw_at = now + interval;
+                FrameAction::Draw
+            }
+            None => FrameAction::Draw,
+        }
+    }
+
+    fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    fn pause_between_loops(&mut self) {
+        self.deadline += Duration::from_millis(50);
+    }
+
+    // After a pause the old deadlines are meaningless; playback restarts the clock from here.
+    fn resume(&mut self, now: Instant) {
+        self.deadline = now;
+    }
+}
+
+// `speed` and `fps` are not read here: they shape `frame_delays`, which callers adjust before playback.
+// A `loop_count` of None follows the same rule decoding uses for a GIF of this many frames.
 pub async fn display_ascii_animation(
     frames: &[Vec<String>],
     frame_delays: &[u16],
-    loop_count: u16,
-    clear_on_exit: bool,
+    options: &PlaybackOptions,
 ) -> Result<()> {
+    let loop_count = options.loop_count.unwrap_or_else(|| default_loop_count(frames.len()));
+    let clear_between_frames = options.clear_between_frames;
     validate_animation_input(frames, frame_delays, loop_count)?;
+    let frame_delays = &*normalize_frame_delays(frame_delays);
+    let min_frame_interval = options.max_fps
+        .filter(|fps| fps.is_finite() && *fps > 0.0)
+        .map(|fps| Duration::from_secs_f64(1.0 / fps as f64));
     
     let mut stdout = io::stdout();
     
@@ -129,121 +357,306 @@ pub async fn display_ascii_animation(
     };
     
     let mut current_iteration = 0;
+
+    // Without per-frame clears, start from a clean screen once and let each frame overwrite the last.
+    if !clear_between_frames {
+        execute!(stdout, Clear(ClearType::All))
+            .map_err(|e| MonochoraError::Terminal(format!("Failed to clear screen: {}", e)))?;
+    }
+
+    let mut schedule = PlaybackSchedule::new(Instant::now(), min_frame_interval);
+    let mut dropped_frames = 0usize;
+    let mut total_frames = 0usize;
     
     'outer: while current_iteration < iterations {
         for (frame_idx, frame) in frames.iter().enumerate() {
-            execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))
-                .map_err(|e| MonochoraError::Terminal(format!("Failed to clear screen: {}", e)))?;
-            
-            for (line_idx, line) in frame.iter().enumerate() {
-                match writeln!(stdout, "{}", line) {
-                    Ok(_) => {},
-                    Err(e) => {
-                        warn!("Failed to write line {} of frame {}: {}", line_idx, frame_idx, e);
-                    }
+            let delay = resolve_frame_delay(frame_delays, frame_idx);
+            total_frames += 1;
+
+            match schedule.next_frame(delay, Instant::now()) {
+                FrameAction::Draw => draw_frame(&mut stdout, frame, frame_idx, clear_between_frames)?,
+                FrameAction::DropLate => {
+                    debug!("Dropping frame {}: playback is running behind", frame_idx);
+                    dropped_frames += 1;
+                }
+                FrameAction::SkipCapped => {
+                    debug!("Skipping frame {} to stay under the terminal frame rate cap", frame_idx);
+                    dropped_frames += 1;
                 }
             }
             
-            stdout.flush()
-                .map_err(|e| MonochoraError::Terminal(format!("Failed to flush stdout: {}", e)))?;
-            
-            // Calculate frame delay
-            let delay = if frame_idx < frame_delays.len() {
-                let delay_ms = frame_delays[frame_idx];
-                if delay_ms == 0 { 100 } else { delay_ms }
-            } else if !frame_delays.is_empty() {
-                let delay_ms = frame_delays[0];
-                if delay_ms == 0 { 100 } else { delay_ms }
-            } else {
-                100 
-            };
+            sleep_until(schedule.deadline().into()).await;
             
-            sleep(Duration::from_millis(delay as u64)).await;
-            
-            match poll(Duration::from_millis(0)) {
-                Ok(true) => {
-                    match read() {
-                        Ok(Event::Key(key)) => {
-                            match key.code {
-                                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
-                                    debug!("User requested exit");
-                                    break 'outer;
-                                }
-                                KeyCode::Char('p') | KeyCode::Char('P') => {
-                                    debug!("Animation paused, press any key to continue");
-                                    match read() {
-                                        Ok(_) => debug!("Animation resumed"),
-                                        Err(e) => warn!("Failed to read resume input: {}", e),
-                                    }
-                                }
-                                _ => {
-                                }
-                            }
-                        }
-                        Ok(_) => {
-                        }
-                        Err(e) => {
-                            warn!("Failed to read terminal event: {}", e);
-                        }
-                    }
-                }
-                Ok(false) => {
-                }
-                Err(e) => {
-                    warn!("Failed to poll for terminal events: {}", e);
-                }
+            match poll_playback_input() {
+                PlaybackInput::Quit => break 'outer,
+                PlaybackInput::Resumed => schedule.resume(Instant::now()),
+                PlaybackInput::Continue => {}
             }
         }
         
         current_iteration += 1;
         
         if current_iteration < iterations {
-            sleep(Duration::from_millis(50)).await;
+            schedule.pause_between_loops();
+            sleep_until(schedule.deadline().into()).await;
         }
     }
     
     execute!(stdout, Show)
         .map_err(|e| MonochoraError::Terminal(format!("Failed to show cursor: {}", e)))?;
     
-    if clear_on_exit {
+    if options.clear_on_exit {
         execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))
             .map_err(|e| MonochoraError::Terminal(format!("Failed to clear screen on exit: {}", e)))?;
     }
+
+    if options.log_dropped_frames {
+        info!("Dropped {} of {} frames during playback", dropped_frames, total_frames);
+    }
     
     Ok(())
 }
 
+const ANSI_RESET: &str = "\x1b[0m";
+
+fn trim_line_end(line: &str) -> String {
+    let (body, reset) = match line.strip_suffix(ANSI_RESET) {
+        Some(body) => (body, ANSI_RESET),
+        None => (line, ""),
+    };
+
+    // Colored spaces carry their own escape sequence; an escape is dropped only when the spaces it
+    // styles were removed, so a run of visible characters behind one escape keeps it.
+    let mut end = body.len();
+    let mut spaces_follow = false;
+    for (start, piece_end, is_escape) in split_escapes(body).into_iter().rev() {
+        let removable = if is_escape { spaces_follow } else { &body[start..piece_end] == " " };
+        if !removable {
+            break;
+        }
+        spaces_follow = true;
+        end = start;
+    }
+
+    let mut trimmed = body[..end].to_string();
+    if !trimmed.is_empty() {
+        trimmed.push_str(reset);
+    }
+    trimmed
+}
+
+pub fn trim_frame_lines(frames: &[Vec<String>]) -> Vec<Vec<String>> {
+    frames
+        .par_iter()
+        .map(|frame| frame.iter().map(|line| trim_line_end(line)).collect())
+        .collect()
+}
+
+// Escape sequences never contain spaces, so a plain replace leaves colors intact.
+pub fn substitute_frame_spaces(frames: &[Vec<String>], replacement: char) -> Vec<Vec<String>> {
+    let replacement = replacement.to_string();
+    frames
+        .par_iter()
+        .map(|frame| frame.iter().map(|line| line.replace(' ', &replacement)).collect())
+        .collect()
+}
+
+pub fn pad_frame_lines(frames: &[Vec<String>]) -> Vec<Vec<String>> {
+    let max_lines = frames.iter().map(|frame| frame.len()).max().unwrap_or(0);
+    let max_width = frames
+        .iter()
+        .flat_map(|frame| frame.iter().map(|line| calculate_line_character_count(line)))
+        .max()
+        .unwrap_or(0);
+
+    frames
+        .par_iter()
+        .map(|frame| {
+            let mut padded: Vec<String> = frame
+                .iter()
+                .map(|line| {
+                    let missing = max_width.saturating_sub(calculate_line_character_count(line));
+                    format!("{}{}", line, " ".repeat(missing))
+                })
+                .collect();
+            padded.resize(max_lines, " ".repeat(max_width));
+            padded
+        })
+        .collect()
+}
+
+// Adds the caption as its own line above or below every frame, centered over the widest line.
+pub fn caption_frame_lines(frames: &[Vec<String>], caption: &str, position: CaptionPosition) -> Vec<Vec<String>> {
+    let max_width = frames
+        .iter()
+        .flat_map(|frame| frame.iter().map(|line| calculate_line_character_count(line)))
+        .max()
+        .unwrap_or(0);
+    let indent = max_width.saturating_sub(caption.chars().count()) / 2;
+    let caption_line = format!("{}{}", " ".repeat(indent), caption);
+
+    frames
+        .par_iter()
+        .map(|frame| {
+            let mut captioned = Vec::with_capacity(frame.len() + 1);
+            match position {
+                CaptionPosition::Top => {
+                    captioned.push(caption_line.clone());
+                    captioned.extend(frame.iter().cloned());
+                }
+                CaptionPosition::Bottom => {
+                    captioned.extend(frame.iter().cloned());
+                    captioned.push(caption_line.clone());
+                }
+            }
+            captioned
+        })
+        .collect()
+}
+
+pub fn number_frame_lines(frames: &[Vec<String>]) -> Vec<Vec<String>> {
+    // Size the gutter from the tallest frame so every frame lines up the same way.
+    let max_lines = frames.iter().map(|frame| frame.len()).max().unwrap_or(0);
+    let gutter_width = max_lines.max(1).to_string().len();
+
+    frames
+        .par_iter()
+        .map(|frame| {
+            frame
+                .iter()
+                .enumerate()
+                .map(|(i, line)| format!("{:>width$} | {}", i + 1, line, width = gutter_width))
+                .collect()
+        })
+        .collect()
+}
+
+pub fn save_ascii_frames_to_dir<P: AsRef<std::path::Path>>(
+    frames: &[Vec<String>],
+    output_dir: P,
+) -> Result<Vec<std::path::PathBuf>> {
+    if frames.is_empty() {
+        return Err(MonochoraError::Animation("No frames to save".to_string()));
+    }
+
+    let output_dir = output_dir.as_ref();
+    std::fs::create_dir_all(output_dir)
+        .map_err(MonochoraError::Io)?;
+
+    debug!("Writing {} frame files to {}", frames.len(), output_dir.display());
+
+    frames
+        .par_iter()
+        .enumerate()
+        .map(|(i, frame)| -> Result<std::path::PathBuf> {
+            let path = output_dir.join(frame_file_name(i, frames.len(), "txt"));
+            let mut content = frame.join("\n");
+            content.push('\n');
+            std::fs::write(&path, content)
+                .map_err(MonochoraError::Io)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+pub fn strip_ansi(line: &str) -> String {
+    if !line.contains('\x1b') {
+        return line.to_string();
+    }
+
+    split_escapes(line)
+        .into_iter()
+        .filter(|&(_, _, is_escape)| !is_escape)
+        .map(|(start, end, _)| &line[start..end])
+        .collect()
+}
+
+pub fn strip_frame_colors(frames: &[Vec<String>]) -> Vec<Vec<String>> {
+    frames
+        .par_iter()
+        .map(|frame| frame.iter().map(|line| strip_ansi(line)).collect())
+        .collect()
+}
+
+const FRAME_SEPARATOR_WIDTH: usize = 80;
+
 pub fn save_ascii_to_file<P: AsRef<std::path::Path>>(
     frames: &[Vec<String>],
     path: P,
+) -> Result<()> {
+    save_ascii_to_file_with_separators(frames, path, true)
+}
+
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct TextFileOptions {
+    pub separators: bool,
+    pub crlf: bool,
+    pub rle: bool,
+    // When set, each separator header also records how long its frame is shown ("Frame 3 (delay: 80ms)"),
+    // so the file carries the timing needed to replay or re-encode it.
+    pub delays: Vec<u16>,
+}
+
+impl Default for TextFileOptions {
+    fn default() -> Self {
+        Self {
+            separators: true,
+            crlf: false,
+            rle: false,
+            delays: Vec::new(),
+        }
+    }
+}
+
+// Without separators the file is a plain run of frames, so colored output plays back with `cat file.ans`.
+pub fn save_ascii_to_file_with_separators<P: AsRef<std::path::Path>>(
+    frames: &[Vec<String>],
+    path: P,
+    separators: bool,
+) -> Result<()> {
+    save_ascii_to_file_with_options(frames, path, &TextFileOptions { separators, ..Default::default() })
+}
+
+// Same layout as the plain text file, run-length encoded as a whole; `decode_rle_text` restores it exactly.
+pub fn save_rle_ascii_to_file<P: AsRef<std::path::Path>>(
+    frames: &[Vec<String>],
+    path: P,
+    separators: bool,
+) -> Result<()> {
+    save_ascii_to_file_with_options(frames, path, &TextFileOptions { separators, rle: true, ..Default::default() })
+}
+
+pub fn save_ascii_to_file_with_options<P: AsRef<std::path::Path>>(
+    frames: &[Vec<String>],
+    path: P,
+    options: &TextFileOptions,
 ) -> Result<()> {
     use std::fs::File;
     
+    let path_ref = path.as_ref();
+    let newline = if options.crlf { "\r\n" } else { "\n" };
+    
     if frames.is_empty() {
         return Err(MonochoraError::Animation("No frames to save".to_string()));
     }
     
-    let path_ref = path.as_ref();
-    
-    if let Some(parent) = path_ref.parent() {
-        if !parent.exists() {
-            return Err(MonochoraError::Io(
-                std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    format!("Parent directory does not exist: {}", parent.display())
-                )
-            ));
-        }
+    if let Some(parent) = path_ref.parent()
+        && !parent.as_os_str().is_empty()
+        && !parent.exists() {
+        return Err(MonochoraError::Io(
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Parent directory does not exist: {}", parent.display())
+            )
+        ));
     }
     
     let file = File::create(path_ref)
-        .map_err(|e| MonochoraError::Io(e))?;
+        .map_err(MonochoraError::Io)?;
     let mut writer = BufWriter::new(file);
     
-    let separator = match String::from_utf8(vec![b'='; 80]) {
-        Ok(s) => s,
-        Err(_) => "=".repeat(80), 
-    };
+    let separator = "=".repeat(FRAME_SEPARATOR_WIDTH);
     
     debug!("Processing {} frames for file save", frames.len());
     
@@ -253,23 +666,32 @@ pub fn save_ascii_to_file<P: AsRef<std::path::Path>>(
         .map(|(i, frame)| -> Result<String> {
             let mut frame_content = String::new();
             
-            frame_content.push_str(&separator);
-            frame_content.push('\n');
-            frame_content.push_str(&format!("Frame {}\n", i + 1));
-            frame_content.push_str(&separator);
-            frame_content.push('\n');
+            if options.separators {
+                frame_content.push_str(&separator);
+                frame_content.push_str(newline);
+                match options.delays.get(i) {
+                    Some(delay) => frame_content.push_str(&format!("Frame {} (delay: {}ms)", i + 1, delay)),
+                    None => frame_content.push_str(&format!("Frame {}", i + 1)),
+                }
+                frame_content.push_str(newline);
+                frame_content.push_str(&separator);
+                frame_content.push_str(newline);
+            }
             
             for line in frame {
                 frame_content.push_str(line);
-                frame_content.push('\n');
+                frame_content.push_str(newline);
             }
-            frame_content.push('\n');
+            frame_content.push_str(newline);
             
             Ok(frame_content)
         })
         .collect();
     
-    let frame_strings = frame_results?;
+    let mut frame_strings = frame_results?;
+    if options.rle {
+        frame_strings = vec![encode_rle_text(&frame_strings.concat())];
+    }
     
     for (idx, frame_string) in frame_strings.iter().enumerate() {
         write!(writer, "{}", frame_string)
@@ -281,16 +703,16 @@ pub fn save_ascii_to_file<P: AsRef<std::path::Path>>(
             ))?;
     }
     
-    use std::io::BufWriter;
     match writer.into_inner() {
-        Ok(file) => {
+        // Pipes and other special files cannot be synced; the write itself already reached the reader.
+        Ok(file) if file.metadata().is_ok_and(|metadata| metadata.is_file()) => {
             file.sync_all()
-                .map_err(|e| MonochoraError::Io(e))?;
+                .map_err(MonochoraError::Io)?;
         }
+        Ok(_) => {}
         Err(into_inner_error) => {
             return Err(MonochoraError::Io(
-                std::io::Error::new(
-                    std::io::ErrorKind::Other,
+                std::io::Error::other(
                     format!("Failed to finalize file write: {}", into_inner_error.error())
                 )
             ));
@@ -300,3 +722,271 @@ pub fn save_ascii_to_file<P: AsRef<std::path::Path>>(
     debug!("Successfully saved {} frames to {}", frames.len(), path_ref.display());
     Ok(())
 }
+
+// Reads back a text file written with frame separators (plain, CRLF or run-length encoded). Headers
+// without a delay get the default one, and colored lines keep their escape sequences.
+pub fn load_ascii_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<(Vec<Vec<String>>, Vec<u16>)> {
+    let content = std::fs::read_to_string(path.as_ref()).map_err(MonochoraError::Io)?;
+    let text = if content.starts_with(RLE_HEADER) {
+        decode_rle_text(&content)?
+    } else {
+        content
+    };
+
+    parse_ascii_text(&text)
+}
+
+fn parse_frame_header(line: &str) -> Option<(usize, Option<u16>)> {
+    let rest = line.strip_prefix("Frame ")?;
+    let (number, delay) = match rest.split_once(" (delay: ") {
+        Some((number, delay)) => (number, Some(delay.strip_suffix("ms)")?.parse().ok()?)),
+        None => (rest, None),
+    };
+    Some((number.parse().ok()?, delay))
+}
+
+fn parse_ascii_text(text: &str) -> Result<(Vec<Vec<String>>, Vec<u16>)> {
+    let separator = "=".repeat(FRAME_SEPARATOR_WIDTH);
+    let lines: Vec<&str> = text
+        .strip_suffix('\n')
+        .unwrap_or(text)
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+
+    let header_at = |i: usize| -> Option<(usize, Option<u16>)> {
+        if lines.get(i) != Some(&separator.as_str()) || lines.get(i + 2) != Some(&separator.as_str()) {
+            return None;
+        }
+        parse_frame_header(lines[i + 1])
+    };
+
+    if header_at(0).is_none() {
+        return Err(MonochoraError::Animation(
+            "No frame header at the start of the file; only text saved with frame separators can be loaded".to_string()
+        ));
+    }
+
+    let mut frames: Vec<Vec<String>> = Vec::new();
+    let mut delays = Vec::new();
+    let mut i = 0;
+
+    // Each frame runs until the next separator-header-separator block, so the scan below only stops at headers.
+    while let Some((number, delay)) = header_at(i) {
+
+        if number != frames.len() + 1 {
+            return Err(MonochoraError::Animation(format!(
+                "Expected the header of frame {}, found frame {} at line {}", frames.len() + 1, number, i + 2
+            )));
+        }
+
+        let start = i + 3;
+        let mut end = start;
+        while end < lines.len() && header_at(end).is_none() {
+            end += 1;
+        }
+
+        // Every frame is followed by one blank line.
+        let mut frame: Vec<String> = lines[start..end].iter().map(|line| line.to_string()).collect();
+        if frame.last().is_some_and(|line| line.is_empty()) {
+            frame.pop();
+        }
+
+        frames.push(frame);
+        delays.push(delay.unwrap_or(DEFAULT_FRAME_DELAY_MS));
+        i = end;
+    }
+
+    debug!("Loaded {} frames from text", frames.len());
+    Ok((frames, delays))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_keeps_a_coalesced_run_of_visible_characters() {
+        let line = "\x1b[38;2;200;10;10mmmmmmmmmmm\x1b[38;2;0;0;0m   \x1b[0m";
+        assert_eq!(trim_line_end(line), "\x1b[38;2;200;10;10mmmmmmmmmmm\x1b[0m");
+    }
+
+    #[test]
+    fn trim_drops_escapes_that_only_color_trailing_spaces() {
+        assert_eq!(trim_line_end("ab\x1b[31m \x1b[1m\x1b[32m  \x1b[0m"), "ab\x1b[0m");
+        assert_eq!(trim_line_end("\x1b[31m   \x1b[0m"), "");
+        assert_eq!(trim_line_end("plain   "), "plain");
+    }
+
+    #[test]
+    fn trim_keeps_an_escape_not_followed_by_spaces() {
+        assert_eq!(trim_line_end("ab\x1b[31m"), "ab\x1b[31m");
+    }
+
+    #[test]
+    fn strip_ansi_ends_escapes_at_the_final_byte() {
+        assert_eq!(strip_ansi("\x1b[38;5;196mmm\x1b[0m x"), "mm x");
+    }
+
+    #[test]
+    fn frames_past_a_short_delay_list_reuse_the_first_delay() {
+        assert_eq!(resolve_frame_delay(&[40, 70], 1), 70);
+        assert_eq!(resolve_frame_delay(&[40, 70], 5), 40);
+        assert_eq!(resolve_frame_delay(&[], 0), DEFAULT_FRAME_DELAY_MS);
+        assert_eq!(resolve_frame_delay(&[0], 3), DEFAULT_FRAME_DELAY_MS);
+    }
+
+    fn sample_frames() -> Vec<Vec<String>> {
+        vec![
+            vec!["ab".to_string(), "cd".to_string()],
+            vec!["ef".to_string(), "gh".to_string()],
+        ]
+    }
+
+    #[test]
+    fn crlf_text_output_ends_every_line_with_crlf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("frames.txt");
+        let options = TextFileOptions { crlf: true, ..TextFileOptions::default() };
+        save_ascii_to_file_with_options(&sample_frames(), &path, &options).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let line_feeds = bytes.iter().filter(|&&byte| byte == b'\n').count();
+        assert!(line_feeds > 0);
+        assert_eq!(bytes.windows(2).filter(|pair| pair == b"\r\n").count(), line_feeds);
+
+        save_ascii_to_file_with_options(&sample_frames(), &path, &TextFileOptions::default()).unwrap();
+        assert!(!std::fs::read(&path).unwrap().contains(&b'\r'));
+    }
+
+    #[test]
+    fn saved_text_loads_back_with_its_frames_and_delays() {
+        let dir = tempfile::tempdir().unwrap();
+        let frames = vec![
+            vec!["\x1b[31m##\x1b[0m  ".to_string(), " .:".to_string()],
+            vec!["@@@@".to_string(), String::new()],
+        ];
+
+        for (name, options) in [
+            ("plain.txt", TextFileOptions { delays: vec![40, 120], ..TextFileOptions::default() }),
+            ("crlf.txt", TextFileOptions { delays: vec![40, 120], crlf: true, ..TextFileOptions::default() }),
+            ("rle.txt", TextFileOptions { delays: vec![40, 120], rle: true, ..TextFileOptions::default() }),
+        ] {
+            let path = dir.path().join(name);
+            save_ascii_to_file_with_options(&frames, &path, &options).unwrap();
+            let (loaded_frames, loaded_delays) = load_ascii_from_file(&path).unwrap();
+            assert_eq!(loaded_frames, frames, "{}", name);
+            assert_eq!(loaded_delays, vec![40, 120], "{}", name);
+        }
+    }
+
+    #[test]
+    fn padding_evens_out_line_counts_and_visible_widths() {
+        let colored = "\x1b[38;2;255;0;0mab\x1b[0m".to_string();
+        let frames = vec![
+            vec!["abcd".to_string()],
+            vec![colored.clone(), "x".to_string(), String::new()],
+            vec![],
+        ];
+
+        let padded = pad_frame_lines(&frames);
+        assert_eq!(padded.len(), 3);
+        for frame in &padded {
+            assert_eq!(frame.len(), 3);
+            assert!(frame.iter().all(|line| calculate_line_character_count(line) == 4), "{:?}", frame);
+        }
+
+        // The escapes count for nothing, so the colored line gets the same two spaces as a plain "ab".
+        assert_eq!(padded[1][0], format!("{}  ", colored));
+        assert_eq!(padded[0], vec!["abcd", "    ", "    "]);
+        assert_eq!(padded[2], vec!["    "; 3]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn text_output_to_a_named_pipe_is_written_straight_through() {
+        use std::os::unix::fs::FileTypeExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("frames.txt");
+        let status = std::process::Command::new("mkfifo").arg(&fifo).status().unwrap();
+        assert!(status.success());
+
+        let reader_path = fifo.clone();
+        let reader = std::thread::spawn(move || std::fs::read_to_string(reader_path).unwrap());
+        let options = TextFileOptions { separators: false, ..TextFileOptions::default() };
+        save_ascii_to_file_with_options(&sample_frames(), &fifo, &options).unwrap();
+
+        assert_eq!(reader.join().unwrap(), "ab\ncd\n\nef\ngh\n\n");
+        assert!(std::fs::metadata(&fifo).unwrap().file_type().is_fifo());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn schedule_absorbs_draw_time_without_drifting() {
+        let start = Instant::now();
+        let mut schedule = PlaybackSchedule::new(start, None);
+        let mut now = start;
+
+        // Every draw takes 30 ms of a 100 ms frame; deadlines stay on the 100 ms grid regardless.
+        for frame in 1..=10u32 {
+            assert_eq!(schedule.next_frame(100, now), FrameAction::Draw);
+            assert_eq!(schedule.deadline(), start + ms(100) * frame);
+            now = schedule.deadline() + ms(30);
+        }
+        assert_eq!(schedule.deadline() - start, ms(1000));
+    }
+
+    #[test]
+    fn schedule_drops_frames_once_playback_falls_behind() {
+        let start = Instant::now();
+        let mut schedule = PlaybackSchedule::new(start, None);
+
+        assert_eq!(schedule.next_frame(50, start + ms(10)), FrameAction::Draw);
+        // A stall to 180 ms overruns the next two deadlines (100 ms and 150 ms).
+        assert_eq!(schedule.next_frame(50, start + ms(180)), FrameAction::DropLate);
+        assert_eq!(schedule.next_frame(50, start + ms(180)), FrameAction::DropLate);
+        assert_eq!(schedule.next_frame(50, start + ms(180)), FrameAction::Draw);
+        assert_eq!(schedule.deadline() - start, ms(200));
+    }
+
+    #[test]
+    fn schedule_skips_frames_inside_the_frame_rate_cap() {
+        let start = Instant::now();
+        let mut schedule = PlaybackSchedule::new(start, Some(ms(100)));
+        let mut actions = Vec::new();
+
+        for _ in 0..6 {
+            let now = schedule.deadline();
+            actions.push(schedule.next_frame(40, now));
+        }
+        assert_eq!(actions, vec![
+            FrameAction::Draw,
+            FrameAction::SkipCapped,
+            FrameAction::SkipCapped,
+            FrameAction::Draw,
+            FrameAction::SkipCapped,
+            FrameAction::SkipCapped,
+        ]);
+        assert_eq!(schedule.deadline() - start, ms(240));
+    }
+
+    #[test]
+    fn schedule_restarts_from_now_after_a_pause() {
+        let start = Instant::now();
+        let mut schedule = PlaybackSchedule::new(start, None);
+
+        schedule.next_frame(100, start);
+        schedule.pause_between_loops();
+        assert_eq!(schedule.deadline() - start, ms(150));
+
+        let resumed_at = start + ms(5000);
+        schedule.resume(resumed_at);
+        assert_eq!(schedule.next_frame(100, resumed_at), FrameAction::Draw);
+        assert_eq!(schedule.deadline() - resumed_at, ms(100));
+    }
+}
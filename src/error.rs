@@ -17,9 +17,11 @@ pub enum MonochoraError {
     #[error("Font does not support the following characters: {characters}")]
     UnsupportedFontCharacters { characters: String },
 
+    #[cfg(feature = "native")]
     #[error("URL parsing error: {0}")]
     UrlParse(#[from] url::ParseError),
 
+    #[cfg(feature = "native")]
     #[error("HTTP request error: {0}")]
     Http(#[from] reqwest::Error),
 
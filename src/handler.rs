@@ -1,9 +1,12 @@
 use gif::DecodeOptions;
+use image::imageops::{self, FilterType};
 use image::{ImageBuffer, Rgba};
 use rayon::prelude::*;
+use std::borrow::Cow;
 use std::fs::File;
+use std::io::Read;
 use std::path::Path;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use crate::{MonochoraError, Result};
 
 #[repr(C)]
@@ -22,6 +25,69 @@ pub struct GifData {
     pub loop_count: u16, 
 }
 
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GifMeta {
+    pub width: u32,
+    pub height: u32,
+    pub frame_count: usize,
+    pub total_duration_ms: u64,
+    pub loop_count: u16,
+    pub interlaced: bool,
+    pub has_transparency: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct DelayStats {
+    pub min_ms: u16,
+    pub max_ms: u16,
+    pub median_ms: u16,
+    pub average_ms: u16,
+    pub total_ms: u64,
+}
+
+pub const DEFAULT_FRAME_DELAY_MS: u16 = 100;
+pub const DEFAULT_MAX_FRAMES: usize = 10000;
+pub const DEFAULT_MAX_DIMENSION: u32 = 65535;
+pub const DEFAULT_MAX_PIXELS: u64 = 100_000_000;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeLimits {
+    pub max_frames: usize,
+    pub max_dimension: u32,
+    pub max_pixels: u64,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_frames: DEFAULT_MAX_FRAMES,
+            max_dimension: DEFAULT_MAX_DIMENSION,
+            max_pixels: DEFAULT_MAX_PIXELS,
+        }
+    }
+}
+
+impl DecodeLimits {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_frames == 0 {
+            return Err(MonochoraError::Config("Maximum frame count must be at least 1".to_string()));
+        }
+
+        if self.max_dimension == 0 {
+            return Err(MonochoraError::Config("Maximum dimension must be at least 1".to_string()));
+        }
+
+        if self.max_pixels == 0 {
+            return Err(MonochoraError::Config("Maximum pixel count must be at least 1".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
 #[repr(C)]
 struct RawFrameData {
     buffer: Vec<u8>,
@@ -41,11 +107,13 @@ impl RawFrameData {
             });
         }
         
-        if self.left >= canvas_width || self.top >= canvas_height {
-            return Err(MonochoraError::GifDecode(
-                format!("Frame position ({}, {}) is outside canvas bounds ({}x{})", 
-                    self.left, self.top, canvas_width, canvas_height)
-            ));
+        // Encoders routinely place frames touching or past the logical screen edge; the overhang is
+        // clipped when compositing rather than failing the whole decode.
+        if self.left + self.width > canvas_width || self.top + self.height > canvas_height {
+            debug!(
+                "Frame {}x{} at ({}, {}) extends past the {}x{} canvas; clipping",
+                self.width, self.height, self.left, self.top, canvas_width, canvas_height
+            );
         }
         
         let expected_size = (self.width * self.height * 4) as usize;
@@ -61,6 +129,10 @@ impl RawFrameData {
 }
 
 pub fn decode_gif<P: AsRef<Path>>(path: P) -> Result<GifData> {
+    decode_gif_with_limits(path, &DecodeLimits::default())
+}
+
+pub fn decode_gif_with_limits<P: AsRef<Path>>(path: P, limits: &DecodeLimits) -> Result<GifData> {
     let path_ref = path.as_ref();
     
     if !path_ref.exists() {
@@ -70,46 +142,97 @@ pub fn decode_gif<P: AsRef<Path>>(path: P) -> Result<GifData> {
     }
     
     let file = File::open(path_ref)
-        .map_err(|e| MonochoraError::Io(e))?;
-    
-    let mut options = DecodeOptions::new();
-    options.set_color_output(gif::ColorOutput::RGBA);
-    
-    let mut decoder = options.read_info(file)
-        .map_err(|e| MonochoraError::GifDecode(format!("Failed to read GIF info: {}", e)))?;
-    
-    let width = decoder.width() as u32;
-    let height = decoder.height() as u32;
-    
-    if width == 0 || height == 0 {
-        return Err(MonochoraError::InvalidDimensions { width, height });
-    }
-    
-    const MAX_DIMENSION: u32 = 65535;
-    const MAX_PIXELS: u64 = 100_000_000; 
-    
-    if width > MAX_DIMENSION || height > MAX_DIMENSION {
-        return Err(MonochoraError::InvalidDimensions { width, height });
-    }
+        .map_err(MonochoraError::Io)?;
     
-    let total_pixels = width as u64 * height as u64;
-    if total_pixels > MAX_PIXELS {
-        return Err(MonochoraError::InsufficientMemory);
+    decode_gif_from_reader_with_limits(file, limits)
+}
+
+pub fn decode_gif_from_bytes(bytes: &[u8]) -> Result<GifData> {
+    decode_gif_from_reader(bytes)
+}
+
+pub fn decode_gif_from_bytes_with_limits(bytes: &[u8], limits: &DecodeLimits) -> Result<GifData> {
+    decode_gif_from_reader_with_limits(bytes, limits)
+}
+
+#[cfg(feature = "archive")]
+pub fn decode_gif_from_archive<P: AsRef<Path>>(archive_path: P, member: Option<&str>) -> Result<GifData> {
+    decode_gif_from_archive_with_limits(archive_path, member, &DecodeLimits::default())
+}
+
+// Without a member name the first `.gif` entry in the archive is used.
+#[cfg(feature = "archive")]
+pub fn decode_gif_from_archive_with_limits<P: AsRef<Path>>(
+    archive_path: P,
+    member: Option<&str>,
+    limits: &DecodeLimits,
+) -> Result<GifData> {
+    let archive_path = archive_path.as_ref();
+    let file = File::open(archive_path)
+        .map_err(MonochoraError::Io)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| MonochoraError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Failed to open archive {}: {}", archive_path.display(), e)
+        )))?;
+
+    let member = match member {
+        Some(name) => name.to_string(),
+        None => archive
+            .file_names()
+            .filter(|name| name.to_ascii_lowercase().ends_with(".gif"))
+            .min()
+            .map(str::to_string)
+            .ok_or_else(|| MonochoraError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No .gif member found in archive {}", archive_path.display())
+            )))?,
+    };
+
+    let mut entry = archive.by_name(&member)
+        .map_err(|e| MonochoraError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Archive member '{}' not found in {}: {}", member, archive_path.display(), e)
+        )))?;
+
+    let mut signature = [0u8; 6];
+    entry.read_exact(&mut signature)
+        .map_err(|_| MonochoraError::GifDecode(format!("Archive member '{}' is not a GIF image", member)))?;
+    if &signature[..3] != b"GIF" {
+        return Err(MonochoraError::GifDecode(format!("Archive member '{}' is not a GIF image", member)));
     }
+
+    info!("Decoding GIF from archive member: {}", member);
+    decode_gif_from_reader_with_limits(std::io::Cursor::new(signature).chain(entry), limits)
+}
+
+pub fn decode_gif_from_reader<R: Read>(reader: R) -> Result<GifData> {
+    decode_gif_from_reader_with_limits(reader, &DecodeLimits::default())
+}
+
+pub fn decode_gif_from_reader_with_limits<R: Read>(reader: R, limits: &DecodeLimits) -> Result<GifData> {
+    let (mut decoder, width, height) = read_gif_header(reader, limits)?;
     
     let mut raw_frames = Vec::new();
     let mut frame_count = 0;
-    const MAX_FRAMES: usize = 10000; 
     
     info!("Decoding GIF: {}x{}", width, height);
     
     while let Ok(Some(frame)) = decoder.read_next_frame() {
-        if frame_count >= MAX_FRAMES {
-            warn!("Reached maximum frame limit of {}, stopping decode", MAX_FRAMES);
+        if frame_count >= limits.max_frames {
+            // Keep walking frame headers (no pixel decoding) so the warning can report the real length.
+            let mut total_frames = frame_count + 1;
+            while let Ok(Some(_)) = decoder.next_frame_info() {
+                total_frames += 1;
+            }
+            warn!(
+                "GIF has {} frames, only the first {} were decoded (raise the frame limit to include more)",
+                total_frames, limits.max_frames
+            );
             break;
         }
         
-        let delay_ms = if frame.delay == 0 { 100 } else { frame.delay * 10 };
+        let delay_ms = frame_delay_ms(frame.delay);
         
         let raw_frame = RawFrameData {
             buffer: frame.buffer.to_vec(),
@@ -129,6 +252,11 @@ pub fn decode_gif<P: AsRef<Path>>(path: P) -> Result<GifData> {
         return Err(MonochoraError::GifDecode("No valid frames found in GIF".to_string()));
     }
     
+    let raw_delays: Vec<u16> = raw_frames.iter().map(|raw_frame| raw_frame.delay_time_ms).collect();
+    for (raw_frame, &delay) in raw_frames.iter_mut().zip(normalize_frame_delays(&raw_delays).iter()) {
+        raw_frame.delay_time_ms = delay;
+    }
+    
     info!("Processing {} frames in parallel...", raw_frames.len());
     
     let canvas_width = width;
@@ -143,7 +271,7 @@ pub fn decode_gif<P: AsRef<Path>>(path: P) -> Result<GifData> {
     
     let frames = frame_results?;
     
-    let loop_count = if frames.len() > 1 { 0 } else { 1 };
+    let loop_count = default_loop_count(frames.len());
     
     Ok(GifData {
         frames,
@@ -153,6 +281,233 @@ pub fn decode_gif<P: AsRef<Path>>(path: P) -> Result<GifData> {
     })
 }
 
+fn read_gif_header<R: Read>(reader: R, limits: &DecodeLimits) -> Result<(gif::Decoder<R>, u32, u32)> {
+    limits.validate()?;
+
+    let mut options = DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    
+    let decoder = options.read_info(reader)
+        .map_err(|e| MonochoraError::GifDecode(format!("Failed to read GIF info: {}", e)))?;
+    
+    let width = decoder.width() as u32;
+    let height = decoder.height() as u32;
+    
+    if width == 0 || height == 0 {
+        return Err(MonochoraError::InvalidDimensions { width, height });
+    }
+    
+    if width > limits.max_dimension || height > limits.max_dimension {
+        warn!("GIF is {}x{}, larger than the maximum dimension of {}", width, height, limits.max_dimension);
+        return Err(MonochoraError::InvalidDimensions { width, height });
+    }
+    
+    let total_pixels = width as u64 * height as u64;
+    if total_pixels > limits.max_pixels {
+        warn!("GIF has {} pixels per frame, more than the maximum of {}", total_pixels, limits.max_pixels);
+        return Err(MonochoraError::InsufficientMemory);
+    }
+
+    Ok((decoder, width, height))
+}
+
+// Decodes one frame at a time, so playback can start before the whole file has been read.
+pub struct GifFrameStream<R: Read> {
+    decoder: gif::Decoder<R>,
+    width: u32,
+    height: u32,
+    max_frames: usize,
+    decoded: usize,
+    finished: bool,
+}
+
+impl<R: Read> GifFrameStream<R> {
+    pub fn new(reader: R) -> Result<Self> {
+        Self::with_limits(reader, &DecodeLimits::default())
+    }
+
+    pub fn with_limits(reader: R, limits: &DecodeLimits) -> Result<Self> {
+        let (decoder, width, height) = read_gif_header(reader, limits)?;
+        info!("Streaming GIF: {}x{}", width, height);
+
+        Ok(Self {
+            decoder,
+            width,
+            height,
+            max_frames: limits.max_frames,
+            decoded: 0,
+            finished: false,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl<R: Read> Iterator for GifFrameStream<R> {
+    type Item = Result<GifFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        if self.decoded >= self.max_frames {
+            self.finished = true;
+            if let Ok(Some(_)) = self.decoder.next_frame_info() {
+                warn!("Stopped streaming after {} frames (raise the frame limit to include more)", self.max_frames);
+            }
+            return None;
+        }
+
+        let frame = match self.decoder.read_next_frame() {
+            Ok(Some(frame)) => frame,
+            Ok(None) => {
+                self.finished = true;
+                return None;
+            }
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(MonochoraError::GifDecode(format!("Failed to decode frame {}: {}", self.decoded, e))));
+            }
+        };
+
+        let raw_frame = RawFrameData {
+            buffer: frame.buffer.to_vec(),
+            delay_time_ms: fill_zero_delays(&[frame_delay_ms(frame.delay)])[0],
+            width: frame.width as u32,
+            height: frame.height as u32,
+            left: frame.left as u32,
+            top: frame.top as u32,
+        };
+        self.decoded += 1;
+
+        Some(
+            raw_frame.validate(self.width, self.height)
+                .and_then(|_| create_frame_from_raw(raw_frame, self.width, self.height))
+        )
+    }
+}
+
+pub fn probe_gif<P: AsRef<Path>>(path: P) -> Result<GifMeta> {
+    let path_ref = path.as_ref();
+
+    let file = File::open(path_ref)
+        .map_err(MonochoraError::Io)?;
+
+    let mut decoder = DecodeOptions::new().read_info(file)
+        .map_err(|e| MonochoraError::GifDecode(format!("Failed to read GIF info: {}", e)))?;
+
+    let width = decoder.width() as u32;
+    let height = decoder.height() as u32;
+
+    if width == 0 || height == 0 {
+        return Err(MonochoraError::InvalidDimensions { width, height });
+    }
+
+    let mut delays = Vec::new();
+    let mut interlaced = false;
+    let mut has_transparency = false;
+    while let Some(frame) = decoder.next_frame_info()
+        .map_err(|e| MonochoraError::GifDecode(format!("Failed to read frame header: {}", e)))?
+    {
+        delays.push(frame_delay_ms(frame.delay));
+        interlaced |= frame.interlaced;
+        has_transparency |= frame.transparent.is_some();
+    }
+
+    if delays.is_empty() {
+        return Err(MonochoraError::GifDecode("No valid frames found in GIF".to_string()));
+    }
+
+    Ok(GifMeta {
+        width,
+        height,
+        frame_count: delays.len(),
+        total_duration_ms: sum_delays(fill_zero_delays(&delays).iter().copied()),
+        loop_count: default_loop_count(delays.len()),
+        interlaced,
+        has_transparency,
+    })
+}
+
+// Zero delays are kept as decoded so normalize_frame_delays can tell an untimed animation apart.
+fn frame_delay_ms(delay_centiseconds: u16) -> u16 {
+    delay_centiseconds.saturating_mul(10)
+}
+
+// What decoding reports for a GIF of this many frames: a still image plays once, an animation loops forever.
+pub(crate) fn default_loop_count(frame_count: usize) -> u16 {
+    if frame_count > 1 { 0 } else { 1 }
+}
+
+// Players show a zero delay as the default delay; this is that substitution without the warning.
+fn fill_zero_delays(frame_delays: &[u16]) -> Cow<'_, [u16]> {
+    if !frame_delays.contains(&0) {
+        return Cow::Borrowed(frame_delays);
+    }
+
+    Cow::Owned(frame_delays.iter()
+        .map(|&delay| if delay == 0 { DEFAULT_FRAME_DELAY_MS } else { delay })
+        .collect())
+}
+
+// The one place zero delays are resolved: decoding runs every animation through it, and the display and
+// encoders run caller-supplied delays through it. An animation whose delays are all zero has no timing
+// at all, so every frame gets the default delay (a fixed frame rate applied afterwards replaces it).
+pub fn normalize_frame_delays(frame_delays: &[u16]) -> Cow<'_, [u16]> {
+    if !frame_delays.is_empty() && frame_delays.iter().all(|&delay| delay == 0) {
+        warn!(
+            "All {} frames have zero delay, using {} ms per frame unless a frame rate is set",
+            frame_delays.len(), DEFAULT_FRAME_DELAY_MS
+        );
+    }
+
+    fill_zero_delays(frame_delays)
+}
+
+// A fixed frame rate replaces every delay; otherwise a speed multiplier scales them (never below 1 ms).
+pub fn adjust_frame_delays(frame_delays: &[u16], speed: Option<f32>, fps: Option<f32>) -> Vec<u16> {
+    if let Some(target_fps) = fps {
+        vec![(1000.0 / target_fps) as u16; frame_delays.len()]
+    } else if let Some(speed_mult) = speed {
+        frame_delays.iter()
+            .map(|&delay| ((delay as f32 / speed_mult) as u16).max(1))
+            .collect()
+    } else {
+        frame_delays.to_vec()
+    }
+}
+
+// Even counts average the two middle delays; an empty list has a median of 0.
+pub fn median_delay(frame_delays: &[u16]) -> u16 {
+    let mut delays = frame_delays.to_vec();
+    delays.sort_unstable();
+
+    match delays.len() {
+        0 => 0,
+        len if len.is_multiple_of(2) => ((delays[len / 2 - 1] as u32 + delays[len / 2] as u32) / 2) as u16,
+        len => delays[len / 2],
+    }
+}
+
+fn sum_delays(delays: impl Iterator<Item = u16>) -> u64 {
+    delays.map(|delay| delay as u64).sum()
+}
+
+fn average_delay(total_duration_ms: u64, frame_count: usize) -> u16 {
+    if frame_count == 0 {
+        return DEFAULT_FRAME_DELAY_MS;
+    }
+
+    (total_duration_ms / frame_count as u64) as u16
+}
+
 fn create_frame_from_raw(
     raw_frame: RawFrameData, 
     canvas_width: u32, 
@@ -161,6 +516,7 @@ fn create_frame_from_raw(
     let canvas_size = (canvas_width * canvas_height * 4) as usize;
     let mut buffer = vec![0u8; canvas_size];
     
+    // Interlaced frames need no extra pass: read_next_frame already hands rows back in display order.
     for y in 0..raw_frame.height {
         for x in 0..raw_frame.width {
             let canvas_x = raw_frame.left + x;
@@ -193,23 +549,248 @@ fn create_frame_from_raw(
     })
 }
 
+pub fn concat_gifs(segments: Vec<GifData>, canvas: Option<(u32, u32)>) -> Result<GifData> {
+    if segments.len() < 2 {
+        return Err(MonochoraError::Config("Concatenation requires at least two GIFs".to_string()));
+    }
+
+    let (canvas_width, canvas_height) = canvas.unwrap_or_else(|| {
+        let width = segments.iter().map(|gif| gif.width).max().unwrap_or(0);
+        let height = segments.iter().map(|gif| gif.height).max().unwrap_or(0);
+        (width, height)
+    });
+
+    if canvas_width == 0 || canvas_height == 0 {
+        return Err(MonochoraError::InvalidDimensions { width: canvas_width, height: canvas_height });
+    }
+
+    info!("Concatenating {} GIFs onto a {}x{} canvas", segments.len(), canvas_width, canvas_height);
+
+    let loop_count = segments[0].loop_count;
+    let frames: Vec<GifFrame> = segments
+        .into_iter()
+        .flat_map(|gif| gif.frames)
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|frame| fit_frame_to_canvas(frame, canvas_width, canvas_height))
+        .collect();
+
+    Ok(GifData {
+        frames,
+        width: canvas_width,
+        height: canvas_height,
+        loop_count,
+    })
+}
+
+fn fit_frame_to_canvas(frame: GifFrame, canvas_width: u32, canvas_height: u32) -> GifFrame {
+    let (width, height) = frame.image.dimensions();
+    if width == canvas_width && height == canvas_height {
+        return frame;
+    }
+
+    let scale = (canvas_width as f32 / width as f32).min(canvas_height as f32 / height as f32);
+    let scaled_width = ((width as f32 * scale).round() as u32).clamp(1, canvas_width);
+    let scaled_height = ((height as f32 * scale).round() as u32).clamp(1, canvas_height);
+
+    let scaled = imageops::resize(&frame.image, scaled_width, scaled_height, FilterType::Triangle);
+    let mut image = ImageBuffer::from_pixel(canvas_width, canvas_height, Rgba([0, 0, 0, 0]));
+    imageops::overlay(
+        &mut image,
+        &scaled,
+        ((canvas_width - scaled_width) / 2) as i64,
+        ((canvas_height - scaled_height) / 2) as i64,
+    );
+
+    GifFrame {
+        image,
+        delay_time_ms: frame.delay_time_ms,
+    }
+}
+
+// Crops every frame around its center to the given width/height ratio, keeping as much of the source as fits.
+pub fn center_crop_to_aspect(gif: GifData, aspect_ratio: f32) -> Result<GifData> {
+    if !aspect_ratio.is_finite() || aspect_ratio <= 0.0 {
+        return Err(MonochoraError::Config(format!("Invalid crop aspect ratio: {}", aspect_ratio)));
+    }
+
+    let (width, height) = (gif.width, gif.height);
+    if width == 0 || height == 0 {
+        return Err(MonochoraError::InvalidDimensions { width, height });
+    }
+
+    let (crop_width, crop_height) = if width as f32 / height as f32 > aspect_ratio {
+        (((height as f32 * aspect_ratio).round() as u32).clamp(1, width), height)
+    } else {
+        (width, ((width as f32 / aspect_ratio).round() as u32).clamp(1, height))
+    };
+
+    if crop_width == width && crop_height == height {
+        return Ok(gif);
+    }
+
+    let left = (width - crop_width) / 2;
+    let top = (height - crop_height) / 2;
+    debug!("Center-cropping {}x{} frames to {}x{} at ({}, {})", width, height, crop_width, crop_height, left, top);
+
+    let frames = gif.frames
+        .into_par_iter()
+        .map(|frame| GifFrame {
+            image: imageops::crop_imm(&frame.image, left, top, crop_width, crop_height).to_image(),
+            delay_time_ms: frame.delay_time_ms,
+        })
+        .collect();
+
+    Ok(GifData {
+        frames,
+        width: crop_width,
+        height: crop_height,
+        loop_count: gif.loop_count,
+    })
+}
+
+// Drops a final frame that repeats the first one (added by some encoders to close the loop). Its delay goes to
+// the first frame, which is what a looping player would show for that time anyway.
+pub fn trim_loop_frame(mut gif: GifData) -> (GifData, bool) {
+    let frame_count = gif.frames.len();
+    if frame_count < 2 || gif.frames[0].image.as_raw() != gif.frames[frame_count - 1].image.as_raw() {
+        return (gif, false);
+    }
+
+    if let Some(last) = gif.frames.pop() {
+        gif.frames[0].delay_time_ms = gif.frames[0].delay_time_ms.saturating_add(last.delay_time_ms);
+    }
+    debug!("Dropped final frame {} as a duplicate of the first", frame_count);
+    (gif, true)
+}
+
+// Keeps each frame that is on screen at some tick of a target_fps timeline; a dropped frame's delay is
+// added to the kept frame before it, so the total duration is unchanged.
+pub fn decimate_frames(gif: GifData, target_fps: f32) -> Result<GifData> {
+    if !target_fps.is_finite() || target_fps <= 0.0 {
+        return Err(MonochoraError::Config(format!("Invalid target frame rate: {}", target_fps)));
+    }
+
+    let interval_ms = 1000.0 / target_fps as f64;
+    let frame_count = gif.frames.len();
+    let mut frames: Vec<GifFrame> = Vec::with_capacity(frame_count);
+    let mut start_ms = 0u64;
+    let mut next_tick = 0u64;
+
+    for frame in gif.frames {
+        let end_ms = start_ms + frame.delay_time_ms as u64;
+        let tick_ms = (next_tick as f64 * interval_ms).round() as u64;
+
+        match frames.last_mut() {
+            Some(previous) if tick_ms >= end_ms => {
+                previous.delay_time_ms = previous.delay_time_ms.saturating_add(frame.delay_time_ms);
+            }
+            _ => {
+                frames.push(frame);
+                // Advance past every tick that lands inside this frame.
+                next_tick = ((end_ms as f64 / interval_ms).ceil() as u64).max(next_tick + 1);
+            }
+        }
+
+        start_ms = end_ms;
+    }
+
+    debug!("Decimated {} frames to {} at {:.2} FPS", frame_count, frames.len(), target_fps);
+
+    Ok(GifData { frames, ..gif })
+}
+
+pub fn interpolate_frames(gif: GifData, steps: u32, limits: &DecodeLimits) -> Result<GifData> {
+    if steps == 0 || gif.frames.len() < 2 {
+        return Ok(gif);
+    }
+
+    let frame_count = gif.frames.len() as u64;
+    let output_frames = frame_count + (frame_count - 1) * steps as u64;
+    if output_frames > limits.max_frames as u64 {
+        return Err(MonochoraError::Config(format!(
+            "Interpolating {} frames with {} steps (--interpolate) would produce {} frames, more than the maximum of {} (--max-frames)",
+            frame_count, steps, output_frames, limits.max_frames
+        )));
+    }
+
+    info!("Interpolating {} frames with {} intermediate steps each", frame_count, steps);
+
+    let last = gif.frames.len() - 1;
+    let frames: Vec<GifFrame> = (0..gif.frames.len())
+        .into_par_iter()
+        .flat_map_iter(|idx| {
+            let current = &gif.frames[idx];
+            if idx == last {
+                return vec![current.clone()];
+            }
+
+            let next = &gif.frames[idx + 1];
+            let delays = split_delay(current.delay_time_ms, steps + 1);
+            delays
+                .into_iter()
+                .enumerate()
+                .map(|(step, delay_time_ms)| GifFrame {
+                    image: blend_images(&current.image, &next.image, step as f32 / (steps + 1) as f32),
+                    delay_time_ms,
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(GifData { frames, ..gif })
+}
+
+// Spreads the remainder over the first parts so the parts sum to the original delay.
+fn split_delay(delay_ms: u16, parts: u32) -> Vec<u16> {
+    let base = delay_ms as u32 / parts;
+    let remainder = delay_ms as u32 % parts;
+    (0..parts)
+        .map(|part| (base + u32::from(part < remainder)) as u16)
+        .collect()
+}
+
+fn blend_images(
+    from: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    to: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    t: f32,
+) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    if t == 0.0 || from.dimensions() != to.dimensions() {
+        return from.clone();
+    }
+
+    let mut blended = from.clone();
+    for (out, &target) in blended.iter_mut().zip(to.iter()) {
+        *out = (*out as f32 + (target as f32 - *out as f32) * t).round() as u8;
+    }
+    blended
+}
+
+impl GifMeta {
+    pub fn average_frame_delay(&self) -> u16 {
+        average_delay(self.total_duration_ms, self.frame_count)
+    }
+}
+
 impl GifData {
     pub fn total_duration_ms(&self) -> u64 {
-        self.frames.iter()
-            .map(|frame| frame.delay_time_ms as u64)
-            .sum()
+        sum_delays(self.frames.iter().map(|frame| frame.delay_time_ms))
     }
     
     pub fn average_frame_delay(&self) -> u16 {
-        if self.frames.is_empty() {
-            return 100; // Default delay
+        average_delay(self.total_duration_ms(), self.frames.len())
+    }
+
+    pub fn delay_stats(&self) -> DelayStats {
+        let delays: Vec<u16> = self.frames.iter().map(|frame| frame.delay_time_ms).collect();
+
+        DelayStats {
+            min_ms: delays.iter().copied().min().unwrap_or(0),
+            max_ms: delays.iter().copied().max().unwrap_or(0),
+            median_ms: median_delay(&delays),
+            average_ms: self.average_frame_delay(),
+            total_ms: self.total_duration_ms(),
         }
-        
-        let total: u64 = self.frames.iter()
-            .map(|frame| frame.delay_time_ms as u64)
-            .sum();
-        
-        (total / self.frames.len() as u64) as u16
     }
     
     pub fn validate(&self) -> Result<()> {
@@ -239,3 +820,191 @@ impl GifData {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_test_gif(delays: &[u16]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut bytes, 4, 4, &[0, 0, 0, 255, 255, 255]).unwrap();
+            for (index, &delay) in delays.iter().enumerate() {
+                let mut frame = gif::Frame::from_indexed_pixels(4, 4, &[(index % 2) as u8; 16], None);
+                frame.delay = delay;
+                encoder.write_frame(&frame).unwrap();
+            }
+        }
+        bytes
+    }
+
+    fn decoded_delays(bytes: &[u8]) -> Vec<u16> {
+        decode_gif_from_bytes(bytes).unwrap().frames.iter().map(|frame| frame.delay_time_ms).collect()
+    }
+
+    #[test]
+    fn all_zero_delay_gif_decodes_with_the_default_delay() {
+        let bytes = encode_test_gif(&[0, 0, 0]);
+        assert_eq!(decoded_delays(&bytes), vec![DEFAULT_FRAME_DELAY_MS; 3]);
+    }
+
+    #[test]
+    fn all_zero_delay_gif_takes_the_fps_delay_uniformly() {
+        let delays = decoded_delays(&encode_test_gif(&[0, 0, 0]));
+        assert_eq!(adjust_frame_delays(&delays, None, Some(20.0)), vec![50; 3]);
+        assert_eq!(adjust_frame_delays(&delays, Some(2.0), None), vec![50; 3]);
+    }
+
+    #[test]
+    fn timed_gif_keeps_its_delays() {
+        assert_eq!(decoded_delays(&encode_test_gif(&[4, 0, 12])), vec![40, DEFAULT_FRAME_DELAY_MS, 120]);
+    }
+
+    #[test]
+    fn normalize_only_copies_when_a_delay_is_zero() {
+        assert!(matches!(normalize_frame_delays(&[40, 80]), Cow::Borrowed(_)));
+        assert!(matches!(normalize_frame_delays(&[]), Cow::Borrowed(_)));
+        assert_eq!(&*normalize_frame_delays(&[0, 0]), &[DEFAULT_FRAME_DELAY_MS, DEFAULT_FRAME_DELAY_MS]);
+    }
+
+    fn solid_frame(value: u8, delay_time_ms: u16) -> GifFrame {
+        GifFrame { image: ImageBuffer::from_pixel(2, 2, Rgba([value, value, value, 255])), delay_time_ms }
+    }
+
+    fn two_frame_gif() -> GifData {
+        GifData { frames: vec![solid_frame(0, 100), solid_frame(200, 80)], width: 2, height: 2, loop_count: 0 }
+    }
+
+    #[test]
+    fn interpolating_two_frames_inserts_blended_steps_between_them() {
+        let gif = interpolate_frames(two_frame_gif(), 3, &DecodeLimits::default()).unwrap();
+
+        assert_eq!(gif.frames.len(), 5);
+        let delays: Vec<u16> = gif.frames.iter().map(|frame| frame.delay_time_ms).collect();
+        assert_eq!(delays, vec![25, 25, 25, 25, 80]);
+        let shades: Vec<u8> = gif.frames.iter().map(|frame| frame.image.get_pixel(0, 0)[0]).collect();
+        assert_eq!(shades, vec![0, 50, 100, 150, 200]);
+    }
+
+    #[test]
+    fn interpolating_past_the_frame_limit_is_a_config_error() {
+        let limits = DecodeLimits { max_frames: 4, ..DecodeLimits::default() };
+        match interpolate_frames(two_frame_gif(), 3, &limits) {
+            Err(MonochoraError::Config(message)) => {
+                assert!(message.contains("--interpolate") && message.contains("maximum of 4"), "{}", message);
+            }
+            other => panic!("expected a config error, got {:?}", other.map(|gif| gif.frames.len())),
+        }
+    }
+
+    #[test]
+    fn center_crop_cuts_a_wide_source_down_to_its_middle_square() {
+        // Each column's red channel records its x position, so the crop offset is visible.
+        let image = ImageBuffer::from_fn(40, 10, |x, _| Rgba([x as u8, 0, 0, 255]));
+        let gif = GifData { frames: vec![GifFrame { image, delay_time_ms: 100 }], width: 40, height: 10, loop_count: 0 };
+
+        let cropped = center_crop_to_aspect(gif, 1.0).unwrap();
+        assert_eq!((cropped.width, cropped.height), (10, 10));
+        let frame = &cropped.frames[0];
+        assert_eq!(frame.image.dimensions(), (10, 10));
+        assert_eq!(frame.image.get_pixel(0, 0)[0], 15);
+        assert_eq!(frame.image.get_pixel(9, 9)[0], 24);
+        assert_eq!(frame.delay_time_ms, 100);
+    }
+
+    // Rows in the order an interlaced GIF stores them: every 8th from 0, every 8th from 4,
+    // every 4th from 2, then every odd row.
+    fn interlaced_row_order(height: usize) -> Vec<usize> {
+        [(0, 8), (4, 8), (2, 4), (1, 2)]
+            .iter()
+            .flat_map(|&(start, step)| (start..height).step_by(step))
+            .collect()
+    }
+
+    #[test]
+    fn interlaced_frames_decode_in_display_order() {
+        let (width, height) = (4usize, 10usize);
+        let palette: Vec<u8> = (0..height as u8).flat_map(|row| [row * 20, row * 20, row * 20]).collect();
+        // Each row is filled with its own palette index, so any row mix-up changes the decoded shade.
+        let stored: Vec<u8> = interlaced_row_order(height)
+            .into_iter()
+            .flat_map(|row| vec![row as u8; width])
+            .collect();
+
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut bytes, width as u16, height as u16, &palette).unwrap();
+            let mut frame = gif::Frame::from_indexed_pixels(width as u16, height as u16, &stored, None);
+            frame.interlaced = true;
+            encoder.write_frame(&frame).unwrap();
+        }
+
+        let gif = decode_gif_from_bytes(&bytes).unwrap();
+        let image = &gif.frames[0].image;
+        for y in 0..height as u32 {
+            assert_eq!(image.get_pixel(0, y)[0], y as u8 * 20, "row {}", y);
+        }
+
+        let path = std::env::temp_dir().join(format!("monochora-interlaced-{}.gif", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        let meta = probe_gif(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(meta.unwrap().interlaced);
+    }
+
+    #[test]
+    fn trim_loop_frame_drops_a_last_frame_that_repeats_the_first() {
+        let frames = [0, 60, 120, 180, 0].iter().map(|&value| solid_frame(value, 50)).collect();
+        let gif = GifData { frames, width: 2, height: 2, loop_count: 0 };
+
+        let (gif, trimmed) = trim_loop_frame(gif);
+        assert!(trimmed);
+        assert_eq!(gif.frames.len(), 4);
+        let delays: Vec<u16> = gif.frames.iter().map(|frame| frame.delay_time_ms).collect();
+        assert_eq!(delays, vec![100, 50, 50, 50]);
+
+        let (gif, trimmed) = trim_loop_frame(gif);
+        assert!(!trimmed);
+        assert_eq!(gif.frames.len(), 4);
+    }
+
+    #[test]
+    fn frames_past_the_canvas_edge_are_clipped_instead_of_rejected() {
+        let palette = [0, 0, 0, 255, 0, 0, 0, 0, 255];
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut bytes, 4, 4, &palette).unwrap();
+            encoder.write_frame(&gif::Frame::from_indexed_pixels(4, 4, &[1; 16], None)).unwrap();
+
+            // Half of this frame hangs off the right edge.
+            let mut overhanging = gif::Frame::from_indexed_pixels(4, 2, &[2; 8], None);
+            overhanging.left = 2;
+            encoder.write_frame(&overhanging).unwrap();
+
+            // This one starts exactly at the right edge, so nothing of it is visible.
+            let mut outside = gif::Frame::from_indexed_pixels(2, 2, &[2; 4], None);
+            outside.left = 4;
+            outside.top = 2;
+            encoder.write_frame(&outside).unwrap();
+        }
+
+        let gif = match decode_gif_from_bytes(&bytes) {
+            Ok(gif) => gif,
+            Err(e) => panic!("decoding failed: {}", e),
+        };
+        assert_eq!((gif.width, gif.height), (4, 4));
+        assert_eq!(gif.frames.len(), 3);
+
+        let blue = Rgba([0, 0, 255, 255]);
+        let overhanging = &gif.frames[1].image;
+        assert_eq!(overhanging.dimensions(), (4, 4));
+        for y in 0..2 {
+            assert_eq!(*overhanging.get_pixel(2, y), blue);
+            assert_eq!(*overhanging.get_pixel(3, y), blue);
+        }
+
+        // Nothing of the off-canvas frame lands in its visible area.
+        let outside = &gif.frames[2].image;
+        assert!((2..4).all(|y| (0..4).all(|x| *outside.get_pixel(x, y) != blue)));
+    }
+}
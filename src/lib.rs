@@ -1,15 +1,37 @@
 pub mod converter;
+#[cfg(feature = "native")]
 pub mod display;
 pub mod handler;
 pub mod output;
+pub mod rle;
+#[cfg(feature = "native")]
 pub mod terminal_watcher;
+#[cfg(feature = "native")]
 pub mod web;
 pub mod error;
+#[cfg(feature = "native")]
+pub mod tui;
+#[cfg(feature = "native")]
+pub mod pipeline;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use converter::{image_to_ascii, image_to_colored_ascii, AsciiConverterConfig};
-pub use display::{display_ascii_animation, get_terminal_size, save_ascii_to_file, display_responsive_ascii_animation};
-pub use handler::{decode_gif, GifData, GifFrame};
-pub use output::{ascii_frames_to_gif, ascii_frames_to_gif_with_dimensions, AsciiGifOutputOptions};
+pub use converter::{average_brightness, image_to_ascii, image_to_brightness_grid, image_to_colored_ascii, AsciiConverterConfig, ColorDepth, Colormap, ResampleFilter, BRIGHTNESS_DIGITS};
+#[cfg(feature = "native")]
+pub use display::{display_ascii_animation, get_terminal_size, save_ascii_to_file, save_ascii_to_file_with_separators, save_ascii_to_file_with_options, load_ascii_from_file, save_rle_ascii_to_file, TextFileOptions, save_ascii_frames_to_dir, strip_ansi, strip_frame_colors, trim_frame_lines, number_frame_lines, pad_frame_lines, caption_frame_lines, substitute_frame_spaces, display_responsive_ascii_animation, display_streamed_ascii_animation};
+pub use handler::{adjust_frame_delays, center_crop_to_aspect, concat_gifs, decimate_frames, decode_gif, decode_gif_from_bytes, decode_gif_from_reader, decode_gif_with_limits, interpolate_frames, median_delay, normalize_frame_delays, probe_gif, trim_loop_frame, DecodeLimits, DelayStats, GifData, GifFrame, GifFrameStream, GifMeta};
+pub use output::{ascii_frames_to_gif, ascii_frames_to_gif_bytes, ascii_frames_to_gif_bytes_with_progress, ascii_frames_to_gif_with_dimensions, ascii_frames_to_gif_with_progress, ascii_frames_to_gif_within_size, ascii_frames_to_image_files, ascii_frames_to_png_files, ascii_frames_to_sprite_sheet, layout_frame_lines, remove_partial_outputs, rendered_cell_size, AsciiGifOutputOptions, Caption, CaptionPosition, GifSizeFit, QuantizeMethod, TextLayout};
+#[cfg(feature = "webp")]
+pub use output::ascii_frames_to_webp;
+#[cfg(feature = "archive")]
+pub use handler::{decode_gif_from_archive, decode_gif_from_archive_with_limits};
+#[cfg(feature = "native")]
 pub use terminal_watcher::{TerminalWatcher, ResponsiveFrameManager, TerminalDimensions};
-pub use web::{download_gif_from_url, get_input_path, is_url};
+#[cfg(feature = "native")]
+pub use web::{download_gif_from_url, get_input_path, is_url, InputPath};
+pub use rle::{decode_rle_text, encode_rle_text};
 pub use error::{MonochoraError, Result};
+#[cfg(feature = "native")]
+pub use tui::run_parameter_tweaker;
+#[cfg(feature = "native")]
+pub use pipeline::{convert_animation, AsciiAnimation, InputSource, MonochoraOptions, PlaybackOptions};
@@ -1,28 +1,61 @@
-use clap::Parser;
+use clap::{Arg, Command, CommandFactory, Parser};
 use monochora::{
-    converter::{image_to_ascii, image_to_colored_ascii, AsciiConverterConfig},
-    display::{display_ascii_animation, get_terminal_size, save_ascii_to_file, display_responsive_ascii_animation},
-    handler::decode_gif,
-    output::{ascii_frames_to_gif_with_dimensions, AsciiGifOutputOptions},
+    converter::{average_brightness, image_to_ascii, image_to_colored_ascii, parse_channel_order, AsciiConverterConfig, ColorDepth, Colormap, ResampleFilter, BRIGHTNESS_DIGITS},
+    display::{detect_color_depth, display_ascii_animation, get_terminal_size, save_ascii_to_file_with_options, TextFileOptions, save_ascii_frames_to_dir, strip_frame_colors, trim_frame_lines, number_frame_lines, pad_frame_lines, caption_frame_lines, substitute_frame_spaces, display_responsive_ascii_animation, display_streamed_ascii_animation, load_ascii_from_file},
+    handler::{adjust_frame_delays, center_crop_to_aspect, concat_gifs, decimate_frames, decode_gif_with_limits, interpolate_frames, median_delay, probe_gif, trim_loop_frame, DecodeLimits, GifData, GifFrameStream, DEFAULT_MAX_DIMENSION, DEFAULT_MAX_FRAMES, DEFAULT_MAX_PIXELS},
+    output::{ascii_frames_to_gif_with_dimensions, ascii_frames_to_gif_within_size, ascii_frames_to_image_files, ascii_frames_to_png_files, ascii_frames_to_sprite_sheet, layout_frame_lines, remove_partial_outputs, rendered_cell_size, AsciiGifOutputOptions, Caption, CaptionPosition, QuantizeMethod, TextLayout},
+    pipeline::{convert_frames, PlaybackOptions},
+    rle::decode_rle_text,
     terminal_watcher::{TerminalWatcher, ResponsiveFrameManager, TerminalDimensions},
-    web::get_input_path,
+    tui::run_parameter_tweaker,
+    web::{get_input_path, is_url, InputPath},
     MonochoraError,
 };
 use rayon::prelude::*;
-use std::path::PathBuf;
+use rayon::ThreadPool;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{error, info, warn};
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tracing_subscriber::EnvFilter;
 
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about = "Convert GIF images to ASCII art animations")]
+#[clap(author, version, about = "Convert GIF images to ASCII art animations", args_override_self = true)]
 #[repr(C)]
 struct Args {
     #[clap(short, long, help = "Input GIF file path or URL")]
     input: Option<String>,
 
-    #[clap(short, long, help = "Output file path for text format")]
+    #[clap(long, value_name = "PATH", help = "Also save the GIF downloaded from a URL input to PATH")]
+    keep_download: Option<PathBuf>,
+
+    #[clap(long, help = "Load default option values from a TOML or JSON config file")]
+    config: Option<PathBuf>,
+
+    #[clap(long, num_args = 1.., help = "Additional GIF files or URLs to append after the input as one animation")]
+    concat: Vec<String>,
+
+    #[clap(long, help = "Canvas size for concatenated GIFs as WIDTHxHEIGHT (defaults to the largest input)")]
+    concat_canvas: Option<String>,
+
+    #[clap(long, help = "Start the animation at this time (e.g., 1.5s or 500ms)")]
+    since: Option<String>,
+
+    #[clap(long, help = "Keep only this much of the animation (e.g., 3s or 250ms)")]
+    duration: Option<String>,
+
+    #[clap(short, long, help = "Output file path; the extension selects the format (.txt, .ans, .gif, .webp, .json)")]
     output: Option<PathBuf>,
 
+    #[clap(long, default_value = "auto", value_parser = ["auto", "txt", "ans", "gif", "webp", "json"], help = "Format for --output/--save (auto infers it from the extension)")]
+    output_format: String,
+
     #[clap(short, long, help = "Target width in characters")]
     width: Option<u32>,
     
@@ -32,21 +65,189 @@ struct Args {
     #[clap(short = 'c', long, default_value_t = false, help = "Enable colored output")]
     colored: bool,
 
+    #[clap(long, default_value = "auto", value_parser = ["auto", "truecolor", "256", "16"], help = "Color escapes for colored text output (auto detects the terminal)")]
+    color_depth: String,
+
+    #[clap(long, default_value_t = false, help = "Use the 16 standard ANSI colors for a retro look (same as --color-depth 16)")]
+    ansi16: bool,
+
+    #[clap(long, value_name = "NAME", value_parser = ["viridis", "magma", "grayscale"], help = "Color characters by brightness with a heatmap gradient instead of the source colors")]
+    colormap: Option<String>,
+
+    #[clap(long, default_value_t = 0, help = "Reuse the previous color escape when a neighbor differs by at most N per channel (colored output)")]
+    color_threshold: u8,
+
+    #[clap(long, default_value = "nearest", value_parser = ["nearest", "triangle", "lanczos"], help = "Resize frames to the character grid with this filter before sampling")]
+    resample: String,
+
+    #[clap(long, value_name = "X[,Y]", help = "Shift the sampling grid by a fraction of a cell (0.0-1.0, e.g. 0.5 samples cell centers)")]
+    sample_offset: Option<String>,
+
+    #[clap(long, value_name = "AMOUNT", help = "Move each cell's sample point randomly by up to AMOUNT cells (0.0-1.0) for a dithered look")]
+    sample_jitter: Option<f32>,
+
+    #[clap(long, default_value_t = 0, help = "Seed for --sample-jitter; the same seed gives the same texture")]
+    sample_seed: u64,
+
+    #[clap(long, default_value_t = false, help = "Pick characters from the average luminance of every source pixel a cell covers (colors are still sampled)")]
+    area_luminance: bool,
+
+    #[clap(long, default_value_t = false, help = "Experimental: use denser characters in cells whose source pixels vary a lot, to keep fine detail")]
+    detail_aware: bool,
+
     #[clap(short = 'v', long, default_value_t = false, help = "Invert brightness")] 
     invert: bool,
 
+    #[clap(long, default_value_t = false, help = "Invert brightness automatically when the source is mostly dark")]
+    auto_invert: bool,
+
+    #[clap(long, default_value_t = false, help = "Invert RGB colors per channel before conversion")]
+    invert_colors: bool,
+
+    #[clap(long, help = "Remap color channels before conversion (e.g., rgb->bgr)")]
+    swap_channels: Option<String>,
+
+    #[clap(long, help = "Quantize brightness into N discrete levels before mapping to characters")]
+    posterize: Option<u32>,
+
+    #[clap(long, default_value_t = 0.0, allow_hyphen_values = true, help = "Brightness adjustment (-1.0 to 1.0)")]
+    brightness: f32,
+
+    #[clap(long, default_value_t = 1.0, help = "Contrast multiplier (1.0 = unchanged)")]
+    contrast: f32,
+
+    #[clap(long, default_value_t = 0.0, help = "Lowest brightness (0.0-1.0) used for picking characters; darker cells are raised to it")]
+    min_brightness: f32,
+
+    #[clap(long, default_value_t = 1.0, help = "Highest brightness (0.0-1.0) used for picking characters; brighter cells are lowered to it")]
+    max_brightness: f32,
+
+    #[clap(long, default_value_t = false, help = "Interactively tweak conversion settings on the first frame before converting")]
+    tui: bool,
+
     #[clap(short = 'p', long, default_value_t = false, help = "Use simple character set")] 
     simple: bool,
 
     #[clap(short = 's', long, default_value_t = false, help = "Save to file")]
     save: bool,
+
+    #[clap(long, default_value_t = false, help = "Strip trailing spaces from each line in text output")]
+    trim_lines: bool,
+
+    #[clap(long, default_value_t = false, help = "Prefix each line in text output with its line number")]
+    gutter: bool,
+
+    #[clap(long, default_value_t = false, help = "Pad every frame to the same line count and width")]
+    pad_frames: bool,
+
+    #[clap(long, value_name = "TEXT", help = "Add a caption to every frame: drawn onto rendered images, or as an extra centered line in ASCII output")]
+    caption: Option<String>,
+
+    #[clap(long, default_value = "bottom", value_parser = ["top", "bottom"], help = "Where the caption goes")]
+    caption_position: String,
+
+    #[clap(long, value_name = "COLOR", help = "Caption color for rendered images as R,G,B or #RRGGBB (defaults to the text color)")]
+    caption_color: Option<String>,
+
+    #[clap(long, default_value = "ltr", value_parser = ["ltr", "rtl", "vertical"], help = "Character layout: ltr, rtl (lines run right to left) or vertical (lines become columns, right to left)")]
+    layout: String,
+
+    #[clap(long, default_value_t = false, help = "Write text output without color escape sequences")]
+    strip_color: bool,
+
+    #[clap(long, default_value_t = false, help = "Run-length encode the text file (e.g. '@12' for twelve '@'); restore it with --decode-rle")]
+    rle_text: bool,
+
+    #[clap(long, default_value_t = false, help = "Write each frame's delay into its text file header (e.g. 'Frame 3 (delay: 80ms)')")]
+    text_delays: bool,
+
+    #[clap(long, default_value_t = false, help = "Append a counter (out_1.gif, out_2.gif, ...) instead of overwriting an existing output file")]
+    unique_names: bool,
+
+    #[clap(long, default_value_t = false, help = "Refuse to overwrite existing output files")]
+    no_clobber: bool,
+
+    #[clap(long, default_value_t = false, help = "Overwrite existing output files without a warning")]
+    force: bool,
+
+    #[clap(long, default_value_t = false, help = "Write text output with Windows (CRLF) line endings")]
+    crlf: bool,
+
+    #[clap(long, value_name = "FILE", help = "Print the original text of a file written with --rle-text and exit")]
+    decode_rle: Option<PathBuf>,
+
+    #[clap(long, value_name = "FILE", help = "Re-encode a text animation saved with --save/--output into a GIF instead of converting an input GIF")]
+    from_text: Option<PathBuf>,
+
+    #[clap(long, value_name = "CHAR", help = "Replace spaces with CHAR (e.g., '·' or a non-breaking space) in text and clipboard output")]
+    space_char: Option<char>,
     
     #[clap(long, help = "Generate GIF output. Optionally specify path (e.g., --gif-output or --gif-output path/name.gif)")]
     gif_output: Option<Option<PathBuf>>,
 
+    #[clap(long, help = "Generate an animated WebP file (requires the `webp` feature)")]
+    webp_output: Option<PathBuf>,
+
+    #[clap(long, default_value_t = false, help = "Copy the first frame to the system clipboard (requires the `clipboard` feature)")]
+    clipboard: bool,
+
+    #[clap(long, help = "Write each converted frame as a numbered file into this directory")]
+    frames_dir: Option<PathBuf>,
+
+    #[clap(long, default_value = "txt", value_parser = ["txt", "png"], help = "File format for --frames-dir (txt or png)")]
+    frames_format: String,
+
+    #[clap(long, help = "Render each frame to a numbered image file in this directory")]
+    image_frames_dir: Option<PathBuf>,
+
+    #[clap(long, default_value = "png", value_parser = ["png", "ppm"], help = "Image format for --image-frames-dir (png or ppm)")]
+    image_format: String,
+
+    #[clap(long, help = "Render every frame into a single grid PNG (sprite sheet)")]
+    sprite_sheet: Option<PathBuf>,
+
+    #[clap(long, help = "Number of columns in the sprite sheet (default: square-ish grid)")]
+    sprite_cols: Option<u32>,
+
     #[clap(long, default_value_t = 14.0, help = "Font size for GIF output")]
     font_size: f32,
 
+    #[clap(long, value_name = "RATIO", help = "Character cell width as a fraction of the font size for rendered images (defaults to the font's own advance)")]
+    char_width_ratio: Option<f32>,
+
+    #[clap(long = "font-file", help = "Fallback font file for glyphs missing from the embedded font (GIF output, repeatable)")]
+    font_files: Vec<PathBuf>,
+
+    #[clap(long, default_value_t = false, help = "Substitute '?' for characters missing from the font instead of failing (GIF output)")]
+    substitute_missing: bool,
+
+    #[clap(long, default_value_t = false, help = "Write only the changed region of each frame to shrink GIF output")]
+    optimize_gif: bool,
+
+    #[clap(long, default_value_t = false, help = "Keep the source GIF's transparency: cells over transparent pixels stay transparent in GIF output")]
+    transparent: bool,
+
+    #[clap(long, value_name = "TEXT", help = "Embed TEXT (e.g., a title, author or source URL) as a comment in GIF output")]
+    gif_comment: Option<String>,
+
+    #[clap(long, help = "Maximum palette size for colored GIF output (2-256)")]
+    max_colors: Option<usize>,
+
+    #[clap(long, value_name = "N", help = "Shades between background and text color in the palette of monochrome GIF output (1-254, default 128)")]
+    gray_levels: Option<usize>,
+
+    #[clap(long, value_name = "SIZE", help = "Shrink palette, scale and frame count until GIF output fits in SIZE bytes (suffixes K and M are accepted, e.g. 8M)")]
+    max_output_bytes: Option<String>,
+
+    #[clap(long, value_name = "METHOD", value_parser = ["nearest", "dither", "median-cut"], help = "How GIF output maps rendered colors to the palette: nearest (fastest), dither (error diffusion) or median-cut (palette built from the frames)")]
+    quantize: Option<String>,
+
+    #[clap(long, default_value_t = false, help = "Build the colored GIF palette from colors sampled across all frames instead of a generic palette")]
+    global_palette: bool,
+
+    #[clap(long, value_name = "N", help = "Render GIF frames at N times the size and scale them down for anti-aliased glyph edges (1-4)")]
+    supersample: Option<u32>,
+
     #[clap(long, default_value_t = false, help = "White text on black background")]
     white_on_black: bool,
     
@@ -58,6 +259,15 @@ struct Args {
     
     #[clap(long, help = "Scale factor for dimensions")]
     scale: Option<f32>,
+
+    #[clap(long, default_value_t = false, help = "Crop the source around its center to the --width/--height aspect ratio instead of stretching it")]
+    center_crop: bool,
+
+    #[clap(long, help = "Horizontal scale factor (height is kept unless --scale-y is set)")]
+    scale_x: Option<f32>,
+
+    #[clap(long, help = "Vertical scale factor (width is kept unless --scale-x is set)")]
+    scale_y: Option<f32>,
     
     #[clap(long, default_value_t = true, help = "Preserve aspect ratio")]
     preserve_aspect: bool,
@@ -65,27 +275,105 @@ struct Args {
     #[clap(long, help = "Number of threads for parallel processing")]
     threads: Option<usize>,
 
+    #[clap(long, default_value_t = DEFAULT_MAX_FRAMES, help = "Maximum number of frames to decode; longer GIFs are truncated")]
+    max_frames: usize,
+
+    #[clap(long, default_value_t = DEFAULT_MAX_DIMENSION, help = "Reject GIFs wider or taller than this many pixels")]
+    max_dimension: u32,
+
+    #[clap(long, default_value_t = DEFAULT_MAX_PIXELS, help = "Reject GIFs whose frames have more pixels than this")]
+    max_pixels: u64,
+
     #[clap(short = 'q', long, default_value_t = false, help = "Quiet mode")]
     quiet: bool,
 
+    #[clap(long, default_value_t = false, help = "Print nothing except a fatal error message (implies --quiet)")]
+    quiet_errors_only: bool,
+
+    #[clap(long, default_value_t = false, help = "Print the final output path on its own line to stdout, even with --quiet")]
+    print_output_path: bool,
+
     #[clap(long, default_value = "info", help = "Log level (error, warn, info, debug, trace)")]
     log_level: String,
 
+    #[clap(long, default_value = "text", value_parser = ["text", "json"], help = "Log output format (text or json)")]
+    log_format: String,
+
     #[clap(long, help = "Path to custom character set file")]
     charset_file: Option<PathBuf>,
 
     #[clap(long, help = "Inline character set string (ordered from darkest to lightest)")]
     charset: Option<String>,
 
+    #[clap(long, default_value_t = false, help = "Reject character sets containing non-ASCII characters")]
+    ascii_only: bool,
+
     #[clap(long, default_value_t = false, help = "List available character sets and exit")]
     list_charsets: bool,
 
+    #[clap(long, default_value_t = false, help = "Print the effective character set and a black-to-white gradient converted through it, then exit")]
+    charset_preview: bool,
+
+    #[clap(long, default_value_t = false, help = "Print each cell's brightness as a digit from 0 (dark) to 9 (light) instead of ASCII art, for calibrating charsets")]
+    debug_brightness: bool,
+
+    #[clap(long, default_value_t = false, help = "Print the resolved configuration as JSON and exit")]
+    print_config: bool,
+
+    #[clap(long, default_value_t = false, help = "Print frame timing statistics and a delay histogram, then exit")]
+    stats: bool,
+
+    #[clap(long, default_value_t = false, help = "Print the input GIF's metadata as JSON without decoding its pixels, then exit")]
+    probe: bool,
+
+    #[clap(long, value_name = "RUNS", help = "Run decode and conversion (plus rendering for image output) RUNS times, report timings and exit")]
+    benchmark: Option<usize>,
+
     #[clap(long, help = "Speed multiplier for animation (e.g., 0.5 for half speed, 2.0 for double speed)")]
     speed: Option<f32>,
 
     #[clap(long, help = "Target frames per second (overrides speed setting)")]
     fps: Option<f32>,
 
+    #[clap(long, default_value_t = false, help = "Play at a constant frame rate derived from the source's median frame delay")]
+    fps_auto: bool,
+
+    #[clap(long, default_value_t = false, help = "Play frames from last to first")]
+    reverse: bool,
+
+    #[clap(long, default_value_t = false, help = "Play frames forward then backward")]
+    boomerang: bool,
+
+    #[clap(long, value_name = "MS", help = "Insert a blank frame shown for MS milliseconds between loops (GIF/WebP output)")]
+    repeat_gap: Option<u16>,
+
+    #[clap(long, default_value_t = false, help = "Merge consecutive identical ASCII frames, summing their delays")]
+    dedup_frames: bool,
+
+    #[clap(long, value_name = "N", help = "Insert N blended frames between each pair of source frames (total duration is unchanged)")]
+    interpolate: Option<u32>,
+
+    #[clap(long, default_value_t = false, help = "Drop a final frame that duplicates the first, avoiding a stutter at the loop point")]
+    trim_loop_frame: bool,
+
+    #[clap(long, value_name = "FPS", help = "Drop source frames that would not be shown at FPS before converting, folding their delays into the kept frames")]
+    target_fps_decimate: Option<f32>,
+
+    #[clap(long, default_value_t = false, help = "Re-convert and redisplay whenever the local input file changes")]
+    watch_file: bool,
+
+    #[clap(long, default_value_t = false, help = "Redraw frames in place without clearing the screen between them")]
+    no_clear: bool,
+
+    #[clap(long, default_value_t = false, help = "Start terminal playback while the rest of the GIF is still being decoded")]
+    stream: bool,
+
+    #[clap(long, value_name = "FPS", help = "Cap terminal redraws at FPS frames per second, skipping frames to keep time")]
+    max_terminal_fps: Option<f32>,
+
+    #[clap(long, default_value_t = false, help = "Report how many frames terminal playback dropped to keep time")]
+    log_dropped_frames: bool,
+
     #[clap(long, default_value_t = false, help = "Enable responsive mode - auto-adjust when terminal is resized")]
     responsive: bool,
 
@@ -94,493 +382,2362 @@ struct Args {
 }
 
 fn validate_args(args: &Args) -> Result<(), MonochoraError> {
-    if args.input.is_none() {
+    if args.input.is_none() && args.from_text.is_none() {
         return Err(MonochoraError::Config("Input file path or URL is required".to_string()));
     }
 
+    if args.from_text.is_some() {
+        if args.input.is_some() {
+            return Err(MonochoraError::Config(
+                "Cannot use both --input and --from-text at the same time".to_string()
+            ));
+        }
+
+        if !writes_gif(args) {
+            return Err(MonochoraError::Config(
+                "Text input (--from-text) can only be used with GIF output (--gif-output or --output <file.gif>)".to_string()
+            ));
+        }
+
+        if args.probe || args.stats || args.print_config || args.benchmark.is_some() || args.tui || args.watch_file || args.stream || !args.concat.is_empty() {
+            return Err(MonochoraError::Config(
+                "Text input (--from-text) cannot be used with --probe, --stats, --print-config, --benchmark, --tui, --watch-file, --stream or --concat".to_string()
+            ));
+        }
+    }
+
     if args.font_size <= 0.0 || args.font_size > 100.0 {
         return Err(MonochoraError::InvalidFontSize { size: args.font_size });
     }
 
-    if let Some(width) = args.width {
-        if width == 0 || width > 10000 {
-            return Err(MonochoraError::InvalidDimensions { width, height: args.height.unwrap_or(0) });
-        }
+    if let Some(width) = args.width
+        && (width == 0 || width > 10000) {
+        return Err(MonochoraError::InvalidDimensions { width, height: args.height.unwrap_or(0) });
     }
 
-    if let Some(height) = args.height {
-        if height == 0 || height > 10000 {
-            return Err(MonochoraError::InvalidDimensions { width: args.width.unwrap_or(0), height });
-        }
+    if let Some(height) = args.height
+        && (height == 0 || height > 10000) {
+        return Err(MonochoraError::InvalidDimensions { width: args.width.unwrap_or(0), height });
     }
 
-    if let Some(scale) = args.scale {
-        if scale <= 0.0 || scale > 10.0 {
-            return Err(MonochoraError::Config(format!("Invalid scale factor: {}", scale)));
-        }
+    if let Some(scale) = args.scale
+        && (scale <= 0.0 || scale > 10.0) {
+        return Err(MonochoraError::Config(format!("Invalid scale factor: {}", scale)));
     }
 
-    if let Some(threads) = args.threads {
-        if threads == 0 || threads > 1000 {
-            return Err(MonochoraError::Config(format!("Invalid thread count: {}", threads)));
+    for (flag, value) in [("--scale-x", args.scale_x), ("--scale-y", args.scale_y)] {
+        if let Some(scale) = value
+            && (scale <= 0.0 || scale > 10.0) {
+            return Err(MonochoraError::Config(format!("Invalid scale factor for {}: {}", flag, scale)));
         }
     }
 
-    if let Some(speed) = args.speed {
-        if speed <= 0.0 || speed > 100.0 {
-            return Err(MonochoraError::Config(format!("Invalid speed multiplier: {}", speed)));
-        }
+    if args.sample_seed != 0 && args.sample_jitter.is_none() {
+        return Err(MonochoraError::Config(
+            "Sample seed (--sample-seed) can only be used with --sample-jitter".to_string()
+        ));
     }
 
-    if let Some(fps) = args.fps {
-        if fps <= 0.0 || fps > 1000.0 {
-            return Err(MonochoraError::Config(format!("Invalid FPS value: {}", fps)));
+    if args.center_crop {
+        if args.width.is_none() || args.height.is_none() {
+            return Err(MonochoraError::Config(
+                "Center cropping (--center-crop) needs a target aspect ratio from both --width and --height".to_string()
+            ));
+        }
+
+        if args.scale.is_some() || args.scale_x.is_some() || args.scale_y.is_some() {
+            return Err(MonochoraError::Config(
+                "Center cropping (--center-crop) cannot be used with --scale, --scale-x or --scale-y".to_string()
+            ));
         }
     }
 
-    if args.speed.is_some() && args.fps.is_some() {
+    if args.scale.is_some() && (args.scale_x.is_some() || args.scale_y.is_some()) {
         return Err(MonochoraError::Config(
-            "Cannot use both --speed and --fps at the same time".to_string()
+            "Cannot use --scale together with --scale-x/--scale-y".to_string()
         ));
     }
 
-    if args.watch_terminal && !args.responsive {
-        return Err(MonochoraError::Config(
-            "Terminal watching (--watch-terminal) requires responsive mode (--responsive)".to_string()
-        ));
+    if let Some(threads) = args.threads
+        && (threads == 0 || threads > 1000) {
+        return Err(MonochoraError::Config(format!("Invalid thread count: {}", threads)));
     }
 
-    if args.responsive && (args.gif_output.is_some() || args.save || args.output.is_some()) {
-        return Err(MonochoraError::Config(
-            "Responsive mode cannot be used with file output options".to_string()
-        ));
+    if let Some(speed) = args.speed
+        && (speed <= 0.0 || speed > 100.0) {
+        return Err(MonochoraError::Config(format!("Invalid speed multiplier: {}", speed)));
     }
 
-    validate_conflicting_options(args)?;
-    validate_charset_options(args)?;
+    if let Some(fps) = args.fps
+        && (fps <= 0.0 || fps > 1000.0) {
+        return Err(MonochoraError::Config(format!("Invalid FPS value: {}", fps)));
+    }
 
-    Ok(())
-}
+    if let Some(fps) = args.target_fps_decimate {
+        if fps <= 0.0 || fps > 1000.0 {
+            return Err(MonochoraError::Config(format!("Invalid decimation frame rate: {}", fps)));
+        }
 
-fn validate_conflicting_options(args: &Args) -> Result<(), MonochoraError> {
+        if args.interpolate.is_some() {
+            return Err(MonochoraError::Config(
+                "Cannot use both --target-fps-decimate and --interpolate at the same time".to_string()
+            ));
+        }
+    }
 
-    if args.white_on_black && args.black_on_white {
-        return Err(MonochoraError::Config(
-            "Cannot use both --white-on-black and --black-on-white at the same time".to_string()
-        ));
+    if !(-1.0..=1.0).contains(&args.brightness) {
+        return Err(MonochoraError::Config(format!("Invalid brightness adjustment: {}", args.brightness)));
     }
 
-    let output_modes = [
-        args.gif_output.is_some(),
-        args.save || args.output.is_some(),
-    ];
-    let active_modes = output_modes.iter().filter(|&&x| x).count();
-    
-    if active_modes > 1 {
+    if args.contrast <= 0.0 || args.contrast > 10.0 {
+        return Err(MonochoraError::Config(format!("Invalid contrast: {}", args.contrast)));
+    }
+
+    for (flag, value) in [("--min-brightness", args.min_brightness), ("--max-brightness", args.max_brightness)] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(MonochoraError::Config(format!("Invalid brightness limit for {}: {} (must be between 0.0 and 1.0)", flag, value)));
+        }
+    }
+
+    if args.min_brightness >= args.max_brightness {
+        return Err(MonochoraError::Config(format!(
+            "Minimum brightness (--min-brightness {}) must be below maximum brightness (--max-brightness {})",
+            args.min_brightness, args.max_brightness
+        )));
+    }
+
+    if let Some(levels) = args.posterize
+        && !(2..=256).contains(&levels) {
+        return Err(MonochoraError::Config(format!("Invalid posterize levels: {} (must be between 2 and 256)", levels)));
+    }
+
+    if let Some(mapping) = &args.swap_channels {
+        parse_channel_order(mapping)?;
+    }
+
+    if args.speed.is_some() && args.fps.is_some() {
         return Err(MonochoraError::Config(
-            "Cannot use multiple output modes simultaneously. Choose one: --gif-output, --save/--output, or terminal display".to_string()
+            "Cannot use both --speed and --fps at the same time".to_string()
         ));
     }
 
-    if (args.white_on_black || args.black_on_white) && args.gif_output.is_none() {
+    if args.fps_auto && args.fps.is_some() {
         return Err(MonochoraError::Config(
-            "Background color options (--white-on-black, --black-on-white) can only be used with --gif-output".to_string()
+            "Cannot use both --fps-auto and --fps at the same time".to_string()
         ));
     }
 
-    if args.font_size != 14.0 && args.gif_output.is_none() {
+    if args.fps_auto && args.speed.is_some() {
         return Err(MonochoraError::Config(
-            "Font size (--font-size) can only be used with --gif-output".to_string()
+            "Cannot use both --fps-auto and --speed at the same time".to_string()
         ));
     }
 
-    if args.fit_terminal && (args.gif_output.is_some() || args.save || args.output.is_some()) {
+    if args.watch_terminal && !args.responsive {
         return Err(MonochoraError::Config(
-            "Terminal fitting (--fit-terminal) cannot be used with file output options".to_string()
+            "Terminal watching (--watch-terminal) requires responsive mode (--responsive)".to_string()
         ));
     }
 
-    Ok(())
-}
+    if args.keep_download.is_some() {
+        if !args.input.as_deref().is_some_and(is_url) {
+            return Err(MonochoraError::Config(
+                "Keeping the download (--keep-download) only works with a URL input".to_string()
+            ));
+        }
 
-fn validate_charset_options(args: &Args) -> Result<(), MonochoraError> {
-    if args.charset.is_some() && args.charset_file.is_some() {
+        if !args.concat.is_empty() {
+            return Err(MonochoraError::Config(
+                "Keeping the download (--keep-download) cannot be used with --concat".to_string()
+            ));
+        }
+    }
+
+    if args.watch_file {
+        let input = args.input.as_deref().unwrap_or_default();
+        if input.starts_with("http://") || input.starts_with("https://") || split_archive_input(input).is_some() {
+            return Err(MonochoraError::Config("File watching (--watch-file) only works with local input files".to_string()));
+        }
+
+        if !args.concat.is_empty() || args.tui || args.print_config || args.stats || args.benchmark.is_some() {
+            return Err(MonochoraError::Config(
+                "File watching (--watch-file) cannot be used with --concat, --tui, --print-config, --stats or --benchmark".to_string()
+            ));
+        }
+    }
+
+    if args.no_clear && (writes_file_output(args) || args.watch_terminal) {
         return Err(MonochoraError::Config(
-            "Cannot use both --charset and --charset-file at the same time".to_string()
+            "No-clear mode (--no-clear) only works with terminal display and cannot be used with --watch-terminal".to_string()
         ));
     }
 
-    let charset_options_count = [
-        args.simple,
-        args.charset.is_some(),
-        args.charset_file.is_some(),
-    ].iter().filter(|&&x| x).count();
+    if let Some(fps) = args.max_terminal_fps {
+        if !fps.is_finite() || fps <= 0.0 {
+            return Err(MonochoraError::Config(format!("Invalid terminal frame rate cap: {} (must be greater than 0)", fps)));
+        }
 
-    if charset_options_count > 1 {
+        if writes_file_output(args) || args.watch_terminal {
+            return Err(MonochoraError::Config(
+                "Frame rate cap (--max-terminal-fps) only works with terminal display and cannot be used with --watch-terminal".to_string()
+            ));
+        }
+    }
+
+    if args.log_dropped_frames && (writes_file_output(args) || args.watch_terminal) {
         return Err(MonochoraError::Config(
-            "Cannot use multiple character set options simultaneously. Choose one: --simple, --charset, or --charset-file".to_string()
+            "Dropped frame logging (--log-dropped-frames) only works with terminal display and cannot be used with --watch-terminal".to_string()
         ));
     }
 
-    if let Some(charset) = &args.charset {
-        validate_charset_string(charset)?;
-    }
+    if args.stream {
+        if writes_file_output(args) || args.responsive || args.watch_file {
+            return Err(MonochoraError::Config(
+                "Streaming playback (--stream) only works with plain terminal display (not file output, --responsive or --watch-file)".to_string()
+            ));
+        }
 
-    Ok(())
-}
+        let needs_all_frames = !args.concat.is_empty()
+            || args.since.is_some()
+            || args.duration.is_some()
+            || args.interpolate.is_some()
+            || args.target_fps_decimate.is_some()
+            || args.trim_loop_frame
+            || args.fps_auto
+            || args.dedup_frames
+            || args.reverse
+            || args.boomerang
+            || args.pad_frames
+            || args.center_crop
+            || args.auto_invert
+            || args.tui
+            || args.stats
+            || args.print_config
+            || args.benchmark.is_some();
+        if needs_all_frames {
+            return Err(MonochoraError::Config(
+                "Streaming playback (--stream) cannot be used with options that need every frame up front (--concat, --since, --duration, --interpolate, --target-fps-decimate, --trim-loop-frame, --fps-auto, --dedup-frames, --reverse, --boomerang, --pad-frames, --center-crop, --auto-invert, --tui, --stats, --print-config, --benchmark)".to_string()
+            ));
+        }
 
-fn validate_charset_string(charset: &str) -> Result<(), MonochoraError> {
-    let chars: Vec<char> = charset.chars().collect();
-    
-    if chars.len() < 2 {
+        if args.max_terminal_fps.is_some() || args.log_dropped_frames {
+            return Err(MonochoraError::Config(
+                "Streaming playback (--stream) cannot be used with --max-terminal-fps or --log-dropped-frames".to_string()
+            ));
+        }
+
+        if split_archive_input(args.input.as_deref().unwrap_or_default()).is_some() {
+            return Err(MonochoraError::Config("Streaming playback (--stream) does not support ZIP archive inputs".to_string()));
+        }
+    }
+
+    if args.responsive && writes_file_output(args) {
         return Err(MonochoraError::Config(
-            "Character set must contain at least 2 characters".to_string()
+            "Responsive mode cannot be used with file output options".to_string()
         ));
     }
-    
-    if chars.len() > 256 {
+
+    if args.concat_canvas.is_some() && args.concat.is_empty() {
         return Err(MonochoraError::Config(
-            "Character set cannot exceed 256 characters".to_string()
+            "Concatenation canvas (--concat-canvas) requires --concat".to_string()
         ));
     }
-    
-    for &ch in &chars {
-        if ch.is_control() && ch != '\t' && ch != '\n' {
+
+    if let Some(canvas) = &args.concat_canvas {
+        parse_canvas_size(canvas)?;
+    }
+
+    if let Some(since) = &args.since {
+        parse_time_ms(since)?;
+    }
+
+    if let Some(duration) = &args.duration
+        && parse_time_ms(duration)? == 0 {
+        return Err(MonochoraError::Config("Duration (--duration) must be greater than zero".to_string()));
+    }
+
+    if let Some(runs) = args.benchmark {
+        if runs == 0 {
+            return Err(MonochoraError::Config("Benchmark runs (--benchmark) must be at least 1".to_string()));
+        }
+
+        if args.tui || args.responsive || args.print_config || args.stats {
             return Err(MonochoraError::Config(
-                format!("Character set contains invalid control character: {:?}", ch)
+                "Benchmark mode (--benchmark) cannot be used with --tui, --responsive, --print-config or --stats".to_string()
             ));
         }
+
+        if split_archive_input(args.input.as_deref().unwrap_or_default()).is_some() {
+            return Err(MonochoraError::Config("Benchmark mode (--benchmark) does not support ZIP archive inputs".to_string()));
+        }
     }
-    
-    let unique_chars: std::collections::HashSet<_> = chars.iter().collect();
-    if unique_chars.len() != chars.len() {
+
+    if args.stats && (args.tui || args.print_config) {
         return Err(MonochoraError::Config(
-            "Character set contains duplicate characters".to_string()
+            "Timing statistics (--stats) cannot be used with --tui or --print-config".to_string()
         ));
     }
-    
-    Ok(())
-}
 
-fn load_charset_from_file(path: &PathBuf) -> Result<String, MonochoraError> {
-    let content = std::fs::read_to_string(path)
-        .map_err(|e| MonochoraError::Config(format!("Failed to read charset file: {}", e)))?;
-    
-    let charset = content.trim().to_string();
-    validate_charset_string(&charset)?;
-    
-    Ok(charset)
+    if args.probe {
+        if args.stats || args.print_config || args.benchmark.is_some() || args.tui || args.watch_file || args.stream || !args.concat.is_empty() {
+            return Err(MonochoraError::Config(
+                "Probe mode (--probe) cannot be used with --stats, --print-config, --benchmark, --tui, --watch-file, --stream or --concat".to_string()
+            ));
+        }
+
+        if split_archive_input(args.input.as_deref().unwrap_or_default()).is_some() {
+            return Err(MonochoraError::Config("Probe mode (--probe) does not support ZIP archive inputs".to_string()));
+        }
+    }
+
+    decode_limits(args).validate()?;
+
+    validate_conflicting_options(args)?;
+    validate_charset_options(args)?;
+
+    Ok(())
+}
+
+fn decode_limits(args: &Args) -> DecodeLimits {
+    DecodeLimits {
+        max_frames: args.max_frames,
+        max_dimension: args.max_dimension,
+        max_pixels: args.max_pixels,
+    }
+}
+
+fn file_output_modes(args: &Args) -> [bool; 7] {
+    [
+        args.gif_output.is_some(),
+        args.webp_output.is_some(),
+        args.clipboard,
+        args.save || args.output.is_some(),
+        args.frames_dir.is_some(),
+        args.image_frames_dir.is_some(),
+        args.sprite_sheet.is_some(),
+    ]
+}
+
+fn writes_file_output(args: &Args) -> bool {
+    file_output_modes(args).iter().any(|&active| active)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Ansi,
+    Gif,
+    Webp,
+    Json,
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "txt" => Some(Self::Text),
+            "ans" => Some(Self::Ansi),
+            "gif" => Some(Self::Gif),
+            "webp" => Some(Self::Webp),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Ansi => "ans",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Json => "json",
+        }
+    }
+}
+
+enum OutputSink {
+    Gif(PathBuf),
+    Webp(PathBuf),
+    Text(PathBuf),
+    Ansi(PathBuf),
+    Json(PathBuf),
+    FramesDir,
+    ImageFramesDir(PathBuf),
+    SpriteSheet,
+    Clipboard,
+    Terminal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OverwritePolicy {
+    Warn,
+    NoClobber,
+    Force,
+}
+
+fn overwrite_policy(args: &Args) -> OverwritePolicy {
+    if args.no_clobber {
+        OverwritePolicy::NoClobber
+    } else if args.force {
+        OverwritePolicy::Force
+    } else {
+        OverwritePolicy::Warn
+    }
+}
+
+// Named pipes are streamed into by another program, so they are written as-is rather than renamed or guarded.
+#[cfg(unix)]
+fn is_named_pipe(path: &Path) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_fifo())
+}
+
+#[cfg(not(unix))]
+fn is_named_pipe(_path: &Path) -> bool {
+    false
+}
+
+fn is_existing_output_file(path: &Path) -> bool {
+    path.exists() && !is_named_pipe(path)
+}
+
+fn has_frame_files(dir: &Path) -> bool {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().any(|entry| entry.file_name().to_string_lossy().starts_with("frame_")))
+        .unwrap_or(false)
+}
+
+// Checked once before conversion so a refused overwrite fails fast and a watched input can keep rewriting its output.
+fn check_output_overwrite(args: &Args) -> Result<(), MonochoraError> {
+    let policy = overwrite_policy(args);
+    if policy == OverwritePolicy::Force {
+        return Ok(());
+    }
+
+    let existing = match resolve_output_sink(args)? {
+        OutputSink::Gif(path) | OutputSink::Webp(path) | OutputSink::Text(path) | OutputSink::Ansi(path) | OutputSink::Json(path) => {
+            is_existing_output_file(&path).then_some(path)
+        }
+        OutputSink::SpriteSheet => args.sprite_sheet.clone().filter(|path| is_existing_output_file(path)),
+        OutputSink::FramesDir => args.frames_dir.clone().filter(|dir| has_frame_files(dir)),
+        OutputSink::ImageFramesDir(dir) => has_frame_files(&dir).then_some(dir),
+        OutputSink::Clipboard | OutputSink::Terminal => None,
+    };
+
+    let Some(path) = existing else {
+        return Ok(());
+    };
+
+    if policy == OverwritePolicy::NoClobber {
+        return Err(MonochoraError::Config(format!(
+            "Refusing to overwrite existing output {} (--no-clobber); remove it or choose another path",
+            path.display()
+        )));
+    }
+
+    warn!("Overwriting existing output: {} (use --no-clobber to refuse or --force to silence this)", path.display());
+    Ok(())
+}
+
+// A single line for scripts, printed even with --quiet: the file written, or the directory for per-frame output.
+fn print_output_path(args: &Args, sink: &OutputSink) {
+    if !args.print_output_path {
+        return;
+    }
+
+    let location = match sink {
+        OutputSink::Gif(path) | OutputSink::Webp(path) | OutputSink::Text(path) | OutputSink::Ansi(path) | OutputSink::Json(path) => Some(path),
+        OutputSink::ImageFramesDir(dir) => Some(dir),
+        OutputSink::SpriteSheet => args.sprite_sheet.as_ref(),
+        OutputSink::FramesDir => args.frames_dir.as_ref(),
+        OutputSink::Clipboard | OutputSink::Terminal => None,
+    };
+
+    if let Some(location) = location {
+        println!("{}", location.display());
+    }
+}
+
+// Format written by --output/--save, or None when neither is set.
+fn output_path_format(args: &Args) -> Result<Option<OutputFormat>, MonochoraError> {
+    if !args.save && args.output.is_none() {
+        return Ok(None);
+    }
+
+    if args.output_format != "auto" {
+        return Ok(OutputFormat::from_name(&args.output_format));
+    }
+
+    let Some(extension) = args.output.as_ref().and_then(|path| path.extension()) else {
+        return Ok(Some(OutputFormat::Text));
+    };
+
+    let extension = extension.to_string_lossy().to_lowercase();
+    OutputFormat::from_name(&extension).map(Some).ok_or_else(|| MonochoraError::Config(format!(
+        "Unsupported output extension '.{}' (expected .txt, .ans, .gif, .webp or .json, or set --output-format)",
+        extension
+    )))
+}
+
+fn output_sink_format(args: &Args) -> Option<OutputFormat> {
+    output_path_format(args).ok().flatten()
+}
+
+fn writes_gif(args: &Args) -> bool {
+    args.gif_output.is_some() || output_sink_format(args) == Some(OutputFormat::Gif)
+}
+
+fn writes_webp(args: &Args) -> bool {
+    args.webp_output.is_some() || output_sink_format(args) == Some(OutputFormat::Webp)
+}
+
+fn writes_text(args: &Args) -> bool {
+    matches!(output_sink_format(args), Some(OutputFormat::Text | OutputFormat::Ansi))
+        || (args.frames_dir.is_some() && args.frames_format == "txt")
+}
+
+fn resolve_output_sink(args: &Args) -> Result<OutputSink, MonochoraError> {
+    let from_text = args.from_text.as_ref().map(|path| path.to_string_lossy());
+    let input = args.input.as_deref().or(from_text.as_deref()).unwrap_or_default();
+    let file_path = |path: PathBuf| if args.unique_names { unique_output_path(path) } else { path };
+
+    if args.gif_output.is_some() {
+        return Ok(OutputSink::Gif(file_path(generate_gif_output_path(input, &args.gif_output))));
+    }
+    if let Some(path) = &args.webp_output {
+        return Ok(OutputSink::Webp(file_path(path.clone())));
+    }
+    if args.clipboard {
+        return Ok(OutputSink::Clipboard);
+    }
+    if args.sprite_sheet.is_some() {
+        return Ok(OutputSink::SpriteSheet);
+    }
+    if args.frames_dir.is_some() {
+        return Ok(OutputSink::FramesDir);
+    }
+    if let Some(dir) = &args.image_frames_dir {
+        return Ok(OutputSink::ImageFramesDir(dir.clone()));
+    }
+
+    let Some(format) = output_path_format(args)? else {
+        return Ok(OutputSink::Terminal);
+    };
+
+    let path = match (&args.output, format) {
+        (Some(path), _) => path.clone(),
+        (None, OutputFormat::Gif) => generate_gif_output_path(input, &Some(None)),
+        (None, format) => generate_default_output_path(input).with_extension(format.extension()),
+    };
+    let path = file_path(path);
+
+    Ok(match format {
+        OutputFormat::Text => OutputSink::Text(path),
+        OutputFormat::Ansi => OutputSink::Ansi(path),
+        OutputFormat::Gif => OutputSink::Gif(path),
+        OutputFormat::Webp => OutputSink::Webp(path),
+        OutputFormat::Json => OutputSink::Json(path),
+    })
+}
+
+fn renders_images(args: &Args) -> bool {
+    writes_gif(args)
+        || writes_webp(args)
+        || args.sprite_sheet.is_some()
+        || args.image_frames_dir.is_some()
+        || (args.frames_dir.is_some() && args.frames_format == "png")
+}
+
+fn resolve_color_depth(args: &Args) -> Result<ColorDepth, MonochoraError> {
+    if args.ansi16 {
+        return Ok(ColorDepth::Ansi16);
+    }
+
+    if args.color_depth != "auto" {
+        return ColorDepth::parse(&args.color_depth);
+    }
+
+    // Saved files and rendered output are not tied to this terminal, so they keep full color unless asked otherwise.
+    if !args.colored || writes_file_output(args) {
+        return Ok(ColorDepth::TrueColor);
+    }
+
+    let depth = detect_color_depth();
+    if !args.quiet {
+        info!("Detected terminal color support: {}", depth.label());
+    }
+    Ok(depth)
+}
+
+fn parse_canvas_size(value: &str) -> Result<(u32, u32), MonochoraError> {
+    let invalid = || MonochoraError::Config(format!("Invalid canvas size '{}', expected WIDTHxHEIGHT", value));
+
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u32 = width.trim().parse().map_err(|_| invalid())?;
+    let height: u32 = height.trim().parse().map_err(|_| invalid())?;
+
+    if width == 0 || height == 0 || width > 10000 || height > 10000 {
+        return Err(MonochoraError::InvalidDimensions { width, height });
+    }
+
+    Ok((width, height))
+}
+
+fn parse_rgb_color(value: &str) -> Result<image::Rgb<u8>, MonochoraError> {
+    let invalid = || MonochoraError::Config(format!("Invalid color '{}', expected R,G,B or #RRGGBB", value));
+
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| invalid());
+        return Ok(image::Rgb([channel(0)?, channel(2)?, channel(4)?]));
+    }
+
+    let channels: Vec<u8> = value
+        .split(',')
+        .map(|part| part.trim().parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    match channels[..] {
+        [r, g, b] => Ok(image::Rgb([r, g, b])),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_byte_size(value: &str) -> Result<u64, MonochoraError> {
+    let invalid = || MonochoraError::Config(format!("Invalid size '{}', expected a byte count such as 500000, 512K or 8M", value));
+
+    let trimmed = value.trim();
+    let (digits, multiplier) = match trimmed.char_indices().last() {
+        Some((idx, 'k' | 'K')) => (&trimmed[..idx], 1024),
+        Some((idx, 'm' | 'M')) => (&trimmed[..idx], 1024 * 1024),
+        _ => (trimmed, 1),
+    };
+
+    let size: u64 = digits.trim().parse().map_err(|_| invalid())?;
+    if size == 0 {
+        return Err(invalid());
+    }
+
+    size.checked_mul(multiplier).ok_or_else(invalid)
+}
+
+fn parse_sample_offset(value: &str) -> Result<[f32; 2], MonochoraError> {
+    let invalid = || MonochoraError::Config(format!("Invalid sample offset '{}', expected X or X,Y between 0.0 and 1.0", value));
+
+    let (x, y) = value.split_once(',').unwrap_or((value, value));
+    let x: f32 = x.trim().parse().map_err(|_| invalid())?;
+    let y: f32 = y.trim().parse().map_err(|_| invalid())?;
+
+    if !(0.0..1.0).contains(&x) || !(0.0..1.0).contains(&y) {
+        return Err(invalid());
+    }
+
+    Ok([x, y])
+}
+
+fn parse_time_ms(value: &str) -> Result<u64, MonochoraError> {
+    let invalid = || MonochoraError::Config(format!("Invalid time '{}', expected a value like 1.5s or 500ms", value));
+
+    let trimmed = value.trim();
+    let (number, scale) = if let Some(ms) = trimmed.strip_suffix("ms") {
+        (ms, 1.0)
+    } else if let Some(secs) = trimmed.strip_suffix('s') {
+        (secs, 1000.0)
+    } else {
+        (trimmed, 1000.0)
+    };
+
+    let number: f64 = number.trim().parse().map_err(|_| invalid())?;
+    if !number.is_finite() || number < 0.0 {
+        return Err(invalid());
+    }
+
+    Ok((number * scale).round() as u64)
+}
+
+fn validate_conflicting_options(args: &Args) -> Result<(), MonochoraError> {
+
+    if args.white_on_black && args.black_on_white {
+        return Err(MonochoraError::Config(
+            "Cannot use both --white-on-black and --black-on-white at the same time".to_string()
+        ));
+    }
+
+    output_path_format(args)?;
+
+    if args.output_format != "auto" && !args.save && args.output.is_none() {
+        return Err(MonochoraError::Config(
+            "Output format (--output-format) can only be used with --output or --save".to_string()
+        ));
+    }
+
+    let output_modes = file_output_modes(args);
+    let active_modes = output_modes.iter().filter(|&&x| x).count();
+    
+    if active_modes > 1 {
+        return Err(MonochoraError::Config(
+            "Cannot use multiple output modes simultaneously. Choose one: --gif-output, --webp-output, --clipboard, --save/--output, --frames-dir, --image-frames-dir, --sprite-sheet, or terminal display".to_string()
+        ));
+    }
+
+    if args.frames_format != "txt" && args.frames_dir.is_none() {
+        return Err(MonochoraError::Config(
+            "Frame file format (--frames-format) can only be used with --frames-dir".to_string()
+        ));
+    }
+
+    if writes_webp(args) && !cfg!(feature = "webp") {
+        return Err(MonochoraError::Config(WEBP_UNAVAILABLE.to_string()));
+    }
+
+    let mut archive_inputs = std::iter::once(args.input.as_deref().unwrap_or_default())
+        .chain(args.concat.iter().map(String::as_str));
+    if !cfg!(feature = "archive") && archive_inputs.any(|input| split_archive_input(input).is_some()) {
+        return Err(MonochoraError::Config(ARCHIVE_UNAVAILABLE.to_string()));
+    }
+
+    if args.clipboard && !cfg!(feature = "clipboard") {
+        return Err(MonochoraError::Config(CLIPBOARD_UNAVAILABLE.to_string()));
+    }
+
+    if args.clipboard && (args.interpolate.is_some() || args.dedup_frames || args.repeat_gap.is_some()) {
+        return Err(MonochoraError::Config(
+            "Clipboard output (--clipboard) copies a single frame and cannot be used with --interpolate, --dedup-frames or --repeat-gap".to_string()
+        ));
+    }
+
+    if args.image_format != "png" && args.image_frames_dir.is_none() {
+        return Err(MonochoraError::Config(
+            "Image format (--image-format) can only be used with --image-frames-dir".to_string()
+        ));
+    }
+
+    if let Some(dir) = &args.image_frames_dir
+        && dir.exists() && !dir.is_dir() {
+        return Err(MonochoraError::Config(
+            format!("Image frames directory is not a directory: {}", dir.display())
+        ));
+    }
+
+    if args.sprite_cols.is_some() && args.sprite_sheet.is_none() {
+        return Err(MonochoraError::Config(
+            "Sprite sheet columns (--sprite-cols) can only be used with --sprite-sheet".to_string()
+        ));
+    }
+
+    if args.sprite_cols == Some(0) {
+        return Err(MonochoraError::Config("Sprite sheet columns (--sprite-cols) must be at least 1".to_string()));
+    }
+
+    if (args.white_on_black || args.black_on_white) && !renders_images(args) {
+        return Err(MonochoraError::Config(
+            "Background color options (--white-on-black, --black-on-white) can only be used with rendered output (--gif-output, --webp-output, --frames-format png, --image-frames-dir, --sprite-sheet)".to_string()
+        ));
+    }
+
+    if args.font_size != 14.0 && !renders_images(args) {
+        return Err(MonochoraError::Config(
+            "Font size (--font-size) can only be used with rendered output (--gif-output, --webp-output, --frames-format png, --image-frames-dir, --sprite-sheet)".to_string()
+        ));
+    }
+
+    if !args.font_files.is_empty() && !renders_images(args) {
+        return Err(MonochoraError::Config(
+            "Fallback fonts (--font-file) can only be used with rendered output (--gif-output, --webp-output, --frames-format png, --image-frames-dir, --sprite-sheet)".to_string()
+        ));
+    }
+
+    if args.substitute_missing && !renders_images(args) {
+        return Err(MonochoraError::Config(
+            "Glyph substitution (--substitute-missing) can only be used with rendered output (--gif-output, --webp-output, --frames-format png, --image-frames-dir, --sprite-sheet)".to_string()
+        ));
+    }
+
+    if args.auto_invert && args.invert {
+        return Err(MonochoraError::Config(
+            "Cannot use both --auto-invert and --invert at the same time".to_string()
+        ));
+    }
+
+    if args.color_depth != "auto" && !args.colored {
+        return Err(MonochoraError::Config(
+            "Color depth (--color-depth) can only be used with colored output (--colored)".to_string()
+        ));
+    }
+
+    if args.ansi16 && !args.colored {
+        return Err(MonochoraError::Config(
+            "ANSI 16-color mode (--ansi16) can only be used with colored output (--colored)".to_string()
+        ));
+    }
+
+    if args.ansi16 && args.color_depth != "auto" {
+        return Err(MonochoraError::Config(
+            "Cannot use both --ansi16 and --color-depth at the same time".to_string()
+        ));
+    }
+
+    if args.colormap.is_some() && !args.colored {
+        return Err(MonochoraError::Config(
+            "Colormap (--colormap) can only be used with colored output (--colored)".to_string()
+        ));
+    }
+
+    if args.color_threshold > 0 && !args.colored {
+        return Err(MonochoraError::Config(
+            "Color merging (--color-threshold) can only be used with colored output (--colored)".to_string()
+        ));
+    }
+
+    if args.max_colors.is_some() && (!writes_gif(args) || !args.colored) {
+        return Err(MonochoraError::Config(
+            "Palette size (--max-colors) can only be used with colored GIF output (--gif-output --colored)".to_string()
+        ));
+    }
+
+    if args.caption.is_none() && (args.caption_position != "bottom" || args.caption_color.is_some()) {
+        return Err(MonochoraError::Config(
+            "Caption position and color (--caption-position, --caption-color) can only be used with --caption".to_string()
+        ));
+    }
+
+    if let Some(caption) = &args.caption {
+        if caption.is_empty() || caption.chars().any(|ch| ch.is_control()) {
+            return Err(MonochoraError::Config(
+                "Caption (--caption) must be a single non-empty line without control characters".to_string()
+            ));
+        }
+
+        if args.stream || args.responsive {
+            return Err(MonochoraError::Config(
+                "Caption (--caption) cannot be used with --stream or --responsive".to_string()
+            ));
+        }
+    }
+
+    if TextLayout::parse(&args.layout)? != TextLayout::Ltr && (args.stream || args.responsive) {
+        return Err(MonochoraError::Config(
+            "Text layout (--layout) cannot be used with --stream or --responsive".to_string()
+        ));
+    }
+
+    if let Some(ratio) = args.char_width_ratio {
+        if !renders_images(args) {
+            return Err(MonochoraError::Config(
+                "Character width ratio (--char-width-ratio) can only be used with image output (--gif-output, --webp-output, --sprite-sheet, --image-frames-dir)".to_string()
+            ));
+        }
+
+        if !(0.1..=2.0).contains(&ratio) {
+            return Err(MonochoraError::Config(format!("Invalid character width ratio: {} (must be between 0.1 and 2.0)", ratio)));
+        }
+    }
+
+    if let Some(color) = &args.caption_color {
+        if !renders_images(args) {
+            return Err(MonochoraError::Config(
+                "Caption color (--caption-color) can only be used with image output (--gif-output, --webp-output, --sprite-sheet, --image-frames-dir)".to_string()
+            ));
+        }
+        parse_rgb_color(color)?;
+    }
+
+    if args.debug_brightness && (args.colored || renders_images(args)) {
+        return Err(MonochoraError::Config(
+            "Brightness grid (--debug-brightness) only works with plain text output (not --colored or image output)".to_string()
+        ));
+    }
+
+    if args.gray_levels.is_some() && (!writes_gif(args) || args.colored) {
+        return Err(MonochoraError::Config(
+            "Gray levels (--gray-levels) can only be used with monochrome GIF output (--gif-output without --colored)".to_string()
+        ));
+    }
+
+    if let Some(gray_levels) = args.gray_levels
+        && !(1..=254).contains(&gray_levels) {
+        return Err(MonochoraError::Config(format!("Invalid gray level count: {} (must be between 1 and 254)", gray_levels)));
+    }
+
+    if let Some(size) = &args.max_output_bytes {
+        if !writes_gif(args) {
+            return Err(MonochoraError::Config(
+                "Output size limit (--max-output-bytes) can only be used with GIF output (--gif-output)".to_string()
+            ));
+        }
+        parse_byte_size(size)?;
+    }
+
+    if args.quantize.is_some() && !writes_gif(args) {
+        return Err(MonochoraError::Config(
+            "Quantization method (--quantize) can only be used with GIF output (--gif-output)".to_string()
+        ));
+    }
+
+    if args.global_palette {
+        if !writes_gif(args) || !args.colored {
+            return Err(MonochoraError::Config(
+                "Global palette (--global-palette) can only be used with colored GIF output (--colored with --gif-output)".to_string()
+            ));
+        }
+
+        if args.quantize.as_deref() == Some("median-cut") {
+            return Err(MonochoraError::Config(
+                "Cannot use both --global-palette and --quantize median-cut at the same time".to_string()
+            ));
+        }
+    }
+
+    if let Some(factor) = args.supersample {
+        if !writes_gif(args) {
+            return Err(MonochoraError::Config(
+                "Supersampling (--supersample) can only be used with GIF output (--gif-output)".to_string()
+            ));
+        }
+
+        if !(1..=4).contains(&factor) {
+            return Err(MonochoraError::Config(format!(
+                "Invalid supersampling factor: {} (must be between 1 and 4)", factor
+            )));
+        }
+    }
+
+    if args.repeat_gap.is_some() && !writes_gif(args) && !writes_webp(args) {
+        return Err(MonochoraError::Config(
+            "Loop gap (--repeat-gap) can only be used with animated output (--gif-output, --webp-output)".to_string()
+        ));
+    }
+
+    if args.repeat_gap == Some(0) {
+        return Err(MonochoraError::Config("Loop gap (--repeat-gap) must be at least 1 ms".to_string()));
+    }
+
+    if args.optimize_gif && !writes_gif(args) {
+        return Err(MonochoraError::Config(
+            "Frame optimization (--optimize-gif) can only be used with GIF output (--gif-output)".to_string()
+        ));
+    }
+
+    if args.transparent && !writes_gif(args) {
+        return Err(MonochoraError::Config(
+            "Transparent output (--transparent) can only be used with GIF output (--gif-output)".to_string()
+        ));
+    }
+
+    if args.transparent && args.optimize_gif {
+        return Err(MonochoraError::Config(
+            "Cannot use both --transparent and --optimize-gif at the same time".to_string()
+        ));
+    }
+
+    if args.gif_comment.is_some() && !writes_gif(args) {
+        return Err(MonochoraError::Config(
+            "GIF comment (--gif-comment) can only be used with GIF output (--gif-output)".to_string()
+        ));
+    }
+
+    if let Some(max_colors) = args.max_colors
+        && !(2..=256).contains(&max_colors) {
+        return Err(MonochoraError::Config(format!("Invalid palette size: {} (must be between 2 and 256)", max_colors)));
+    }
+
+    if let Some(replacement) = args.space_char {
+        if !writes_text(args) && !args.clipboard {
+            return Err(MonochoraError::Config(
+                "Space substitution (--space-char) can only be used with text output (--save/--output or --frames-dir) or --clipboard".to_string()
+            ));
+        }
+
+        if replacement.is_control() {
+            return Err(MonochoraError::Config(format!("Invalid space character {:?} (must be printable)", replacement)));
+        }
+    }
+
+    if args.rle_text && output_sink_format(args) != Some(OutputFormat::Text) {
+        return Err(MonochoraError::Config(
+            "Run-length encoding (--rle-text) can only be used with text file output (--save or --output <file.txt>)".to_string()
+        ));
+    }
+
+    if args.text_delays && output_sink_format(args) != Some(OutputFormat::Text) {
+        return Err(MonochoraError::Config(
+            "Frame delay headers (--text-delays) can only be used with text file output (--save or --output <file.txt>)".to_string()
+        ));
+    }
+
+    if args.no_clobber && args.force {
+        return Err(MonochoraError::Config(
+            "Cannot use both --no-clobber and --force at the same time".to_string()
+        ));
+    }
+
+    if (args.no_clobber || args.force) && (!writes_file_output(args) || args.clipboard) {
+        return Err(MonochoraError::Config(
+            "Overwrite policy (--no-clobber/--force) can only be used with file output".to_string()
+        ));
+    }
+
+    if args.print_output_path && (!writes_file_output(args) || args.clipboard) {
+        return Err(MonochoraError::Config(
+            "Output path printing (--print-output-path) can only be used with file output".to_string()
+        ));
+    }
+
+    if args.unique_names {
+        if !(writes_gif(args) || writes_webp(args) || args.save || args.output.is_some()) {
+            return Err(MonochoraError::Config(
+                "Unique names (--unique-names) can only be used with single-file output (--save/--output, --gif-output or --webp-output)".to_string()
+            ));
+        }
+
+        if args.watch_file {
+            return Err(MonochoraError::Config(
+                "Unique names (--unique-names) cannot be used with --watch-file, which rewrites its output on every change".to_string()
+            ));
+        }
+    }
+
+    if args.crlf && !matches!(output_sink_format(args), Some(OutputFormat::Text | OutputFormat::Ansi)) {
+        return Err(MonochoraError::Config(
+            "CRLF line endings (--crlf) can only be used with text file output (--save/--output)".to_string()
+        ));
+    }
+
+    if args.strip_color && !writes_text(args) {
+        return Err(MonochoraError::Config(
+            "Color stripping (--strip-color) can only be used with text output (--save/--output or --frames-dir)".to_string()
+        ));
+    }
+
+    if args.trim_lines && !writes_text(args) {
+        return Err(MonochoraError::Config(
+            "Line trimming (--trim-lines) can only be used with text output (--save/--output or --frames-dir)".to_string()
+        ));
+    }
+
+    if args.pad_frames && args.trim_lines {
+        return Err(MonochoraError::Config(
+            "Frame padding (--pad-frames) cannot be combined with line trimming (--trim-lines)".to_string()
+        ));
+    }
+
+    if args.gutter && !writes_text(args) {
+        return Err(MonochoraError::Config(
+            "Line numbers (--gutter) can only be used with text output (--save/--output or --frames-dir)".to_string()
+        ));
+    }
+
+    if args.dedup_frames && args.responsive {
+        return Err(MonochoraError::Config(
+            "Frame deduplication (--dedup-frames) cannot be used with --responsive".to_string()
+        ));
+    }
+
+    if args.fit_terminal && writes_file_output(args) {
+        return Err(MonochoraError::Config(
+            "Terminal fitting (--fit-terminal) cannot be used with file output options".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_charset_options(args: &Args) -> Result<(), MonochoraError> {
+    if args.charset.is_some() && args.charset_file.is_some() {
+        return Err(MonochoraError::Config(
+            "Cannot use both --charset and --charset-file at the same time".to_string()
+        ));
+    }
+
+    let charset_options_count = [
+        args.simple,
+        args.charset.is_some(),
+        args.charset_file.is_some(),
+    ].iter().filter(|&&x| x).count();
+
+    if charset_options_count > 1 {
+        return Err(MonochoraError::Config(
+            "Cannot use multiple character set options simultaneously. Choose one: --simple, --charset, or --charset-file".to_string()
+        ));
+    }
+
+    if let Some(charset) = &args.charset {
+        validate_charset_string(charset)?;
+    }
+
+    if args.debug_brightness && charset_options_count > 0 {
+        return Err(MonochoraError::Config(
+            "Brightness grid (--debug-brightness) prints digits and cannot be used with --simple, --charset or --charset-file".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_charset_string(charset: &str) -> Result<(), MonochoraError> {
+    let chars: Vec<char> = charset.chars().collect();
+    
+    if chars.len() < 2 {
+        return Err(MonochoraError::Config(
+            "Character set must contain at least 2 characters".to_string()
+        ));
+    }
+    
+    if chars.len() > 256 {
+        return Err(MonochoraError::Config(
+            "Character set cannot exceed 256 characters".to_string()
+        ));
+    }
+    
+    for &ch in &chars {
+        if ch.is_control() && ch != '\t' && ch != '\n' {
+            return Err(MonochoraError::Config(
+                format!("Character set contains invalid control character: {:?}", ch)
+            ));
+        }
+    }
+    
+    let unique_chars: std::collections::HashSet<_> = chars.iter().collect();
+    if unique_chars.len() != chars.len() {
+        return Err(MonochoraError::Config(
+            "Character set contains duplicate characters".to_string()
+        ));
+    }
+    
+    Ok(())
+}
+
+fn load_charset_from_file(path: &PathBuf) -> Result<String, MonochoraError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| MonochoraError::Config(format!("Failed to read charset file: {}", e)))?;
+    
+    let charset = content.trim().to_string();
+    validate_charset_string(&charset)?;
+    
+    Ok(charset)
+}
+
+const CHARSET_PREVIEW_COLUMNS: u32 = 64;
+const CHARSET_PREVIEW_PIXELS_PER_COLUMN: u32 = 4;
+
+// The gradient goes through the normal converter, so --invert, --brightness, --contrast, --posterize
+// and the clamps show up in the ramp exactly as they would in a conversion.
+fn print_charset_preview(args: &Args) -> Result<(), MonochoraError> {
+    let gradient_width = CHARSET_PREVIEW_COLUMNS * CHARSET_PREVIEW_PIXELS_PER_COLUMN;
+    let gradient = image::RgbaImage::from_fn(gradient_width, CHARSET_PREVIEW_PIXELS_PER_COLUMN, |x, _| {
+        let level = (x * 255 / (gradient_width - 1)) as u8;
+        image::Rgba([level, level, level, 255])
+    });
+
+    let config = AsciiConverterConfig {
+        width: Some(CHARSET_PREVIEW_COLUMNS),
+        height: Some(1),
+        scale_factor: None,
+        scale_x: None,
+        scale_y: None,
+        sample_jitter: 0.0,
+        ..build_converter_config(args, gradient_width, CHARSET_PREVIEW_PIXELS_PER_COLUMN)?
+    };
+    let ramp = image_to_ascii(&gradient, &config)?;
+
+    let charset = config.get_charset();
+    println!("Charset ({} characters, darkest to lightest):", charset.len());
+    println!("  {}", charset.iter().collect::<String>());
+    println!("\nGradient, black to white:");
+    for line in &ramp {
+        println!("  {}", line);
+    }
+    println!("  {:<width$}100%", "0%", width = CHARSET_PREVIEW_COLUMNS as usize - 4);
+    Ok(())
+}
+
+fn print_rle_file(path: &Path) -> Result<(), MonochoraError> {
+    let encoded = std::fs::read_to_string(path).map_err(MonochoraError::Io)?;
+    print!("{}", decode_rle_text(&encoded)?);
+    Ok(())
+}
+
+fn list_available_charsets() {
+    println!("Available Character Sets:\n");
+    
+    println!("Built-in Sets:");
+    println!("  simple:    .:-=+*#%@");
+    println!("  detailed:  .'`^\",:;Il!i><~+_-?][}}{{1)(|\\/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@");
+    
+    println!("\nExample Custom Sets:");
+    println!("  density:    \" .-+*#%@@\"");
+    println!("  minimal:    \" .oO@\"");
+    println!("  technical:  \" .-=+*#\"");
+    println!("  artistic:   \" ·∘○●◉\"");
+    println!("  japanese:   \"・〆ヲァィヵヶ\"");
+    println!("  blocks:     \" ░▒▓█\"");
+    
+    println!("\nUsage:");
+    println!("  --charset \" .oO@\"              # Inline character set");
+    println!("  --charset-file ./my-chars.txt  # Load from file");
+    println!("  --simple                       # Use simple built-in set");
+    println!("  (default)                      # Use detailed built-in set");
+    
+    println!("\nCharacter Set Rules:");
+    println!("  • Order from darkest to lightest");
+    println!("  • Minimum 2 characters, maximum 256");
+    println!("  • Must contain unique characters");
+    println!("  • No control characters (except tab/newline in files)");
+}
+
+fn validate_ascii_charset(chars: &[char]) -> Result<(), MonochoraError> {
+    let non_ascii: String = chars.iter().filter(|ch| !ch.is_ascii()).collect();
+    if !non_ascii.is_empty() {
+        return Err(MonochoraError::Config(
+            format!("Character set contains non-ASCII characters: {:?} (--ascii-only)", non_ascii)
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_custom_charset(args: &Args) -> Result<Option<Vec<char>>, MonochoraError> {
+    if args.debug_brightness {
+        return Ok(Some(BRIGHTNESS_DIGITS.to_vec()));
+    }
+
+    let charset: Option<Vec<char>> = if let Some(charset_string) = &args.charset {
+        Some(charset_string.chars().collect())
+    } else if let Some(charset_file) = &args.charset_file {
+        Some(load_charset_from_file(charset_file)?.chars().collect())
+    } else {
+        None
+    };
+
+    if args.ascii_only {
+        let effective = AsciiConverterConfig {
+            custom_charset: charset.clone(),
+            detailed: !args.simple,
+            ..Default::default()
+        };
+        validate_ascii_charset(effective.get_charset())?;
+    }
+
+    Ok(charset)
+}
+
+fn config_value_to_string(key: &str, value: &serde_json::Value) -> Result<String, MonochoraError> {
+    match value {
+        serde_json::Value::String(s) => Ok(s.clone()),
+        serde_json::Value::Number(n) => Ok(n.to_string()),
+        serde_json::Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(MonochoraError::Config(format!("Unsupported value for config key '{}'", key))),
+    }
+}
+
+fn find_long_arg<'a>(command: &'a Command, long_name: &str) -> Option<&'a Arg> {
+    command.get_arguments()
+        .find(|arg| arg.get_long() == Some(long_name) && arg.get_id() != "config")
+}
+
+// A flag that can be given without a value, which is what a boolean config key turns on.
+fn is_switch(arg: &Arg) -> bool {
+    !arg.get_action().takes_values() || arg.get_num_args().is_some_and(|range| range.min_values() == 0)
+}
+
+// Pulls `--no-<flag>` out of the command line, for switching off a flag the config file turns on.
+fn split_negated_flags(cli_args: Vec<OsString>) -> (Vec<OsString>, Vec<String>) {
+    let command = Args::command();
+    let mut kept = Vec::new();
+    let mut negated = Vec::new();
+
+    for arg in cli_args {
+        let long_name = arg.to_str()
+            .and_then(|arg| arg.strip_prefix("--no-"))
+            .filter(|name| find_long_arg(&command, &format!("no-{}", name)).is_none())
+            .filter(|name| find_long_arg(&command, name).is_some_and(is_switch));
+
+        match long_name {
+            Some(name) => negated.push(name.to_string()),
+            None => kept.push(arg),
+        }
+    }
+
+    (kept, negated)
+}
+
+fn load_config_file_args(path: &Path, negated: &[String]) -> Result<Vec<OsString>, MonochoraError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| MonochoraError::Config(format!("Failed to read config file {}: {}", path.display(), e)))?;
+
+    let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let table: serde_json::Map<String, serde_json::Value> = if is_json {
+        serde_json::from_str(&content)
+            .map_err(|e| MonochoraError::Config(format!("Failed to parse config file {}: {}", path.display(), e)))?
+    } else {
+        toml::from_str(&content)
+            .map_err(|e| MonochoraError::Config(format!("Failed to parse config file {}: {}", path.display(), e)))?
+    };
+
+    let command = Args::command();
+    let mut config_args = Vec::new();
+
+    for (key, value) in &table {
+        let long_name = key.replace('_', "-");
+        let arg = find_long_arg(&command, &long_name)
+            .ok_or_else(|| MonochoraError::Config(format!("Unknown config key: {}", key)))?;
+
+        if negated.contains(&long_name) {
+            continue;
+        }
+
+        let flag = OsString::from(format!("--{}", long_name));
+        let takes_values = arg.get_action().takes_values();
+
+        let values = match value {
+            serde_json::Value::Array(items) => items.iter().collect(),
+            _ => vec![value],
+        };
+
+        for value in values {
+            match value {
+                serde_json::Value::Bool(enabled) if is_switch(arg) => {
+                    if *enabled {
+                        config_args.push(flag.clone());
+                    }
+                }
+                _ if !takes_values => {
+                    return Err(MonochoraError::Config(format!("Config key '{}' expects true or false", key)));
+                }
+                _ => {
+                    config_args.push(flag.clone());
+                    config_args.push(OsString::from(config_value_to_string(key, value)?));
+                }
+            }
+        }
+    }
+
+    Ok(config_args)
+}
+
+fn parse_args() -> Result<Args, MonochoraError> {
+    parse_args_from(std::env::args_os().collect())
+}
+
+// Config values go in front of the command-line arguments, so a flag given on the command line wins.
+fn parse_args_from(mut argv: Vec<OsString>) -> Result<Args, MonochoraError> {
+    let cli_args = argv.split_off(1.min(argv.len()));
+    let (cli_args, negated) = split_negated_flags(cli_args);
+    let args = Args::parse_from(argv.iter().chain(&cli_args));
+
+    let mut args = match &args.config {
+        Some(config_path) => {
+            argv.extend(load_config_file_args(config_path, &negated)?);
+            argv.extend(cli_args);
+
+            Args::parse_from(argv)
+        }
+        None => args,
+    };
+
+    args.quiet |= args.quiet_errors_only;
+    Ok(args)
+}
+
+fn effective_log_level(args: &Args) -> &str {
+    if args.quiet_errors_only {
+        "off"
+    } else if args.quiet && args.log_level.eq_ignore_ascii_case("info") {
+        // Quiet mode also hides the library's routine progress logs unless a level was asked for.
+        "warn"
+    } else {
+        &args.log_level
+    }
+}
+
+fn setup_logging(level: &str, format: &str) -> Result<(), MonochoraError> {
+    let filter = match level.to_lowercase().as_str() {
+        "off" => "off",
+        "error" => "error",
+        "warn" => "warn", 
+        "info" => "info",
+        "debug" => "debug",
+        "trace" => "trace",
+        _ => "info",
+    };
+
+    // RUST_LOG takes precedence so per-module directives work, except when output was silenced outright.
+    let env_filter = match filter {
+        "off" => EnvFilter::new(filter),
+        _ => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(filter)),
+    };
+
+    let builder = tracing_subscriber::fmt().with_env_filter(env_filter);
+    let result = match format {
+        "json" => builder.json().try_init(),
+        _ => builder.try_init(),
+    };
+    result.map_err(|e| MonochoraError::Config(format!("Failed to initialize logging: {}", e)))?;
+
+    Ok(())
+}
+
+fn setup_thread_pool(thread_count: Option<usize>, quiet: bool) -> Result<Arc<ThreadPool>, MonochoraError> {
+    // A local pool keeps --threads from touching rayon's global pool; 0 lets rayon pick the default.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(thread_count.unwrap_or(0))
+        .build()
+        .map_err(|e| MonochoraError::ThreadPool(e.to_string()))?;
+
+    if let Some(threads) = thread_count
+        && !quiet {
+        info!("Using {} threads for parallel processing", threads);
+    }
+
+    Ok(Arc::new(pool))
+}
+
+fn calculate_gif_dimensions(
+    args: &Args, 
+    gif_width: u32, 
+    gif_height: u32
+) -> Result<(Option<u32>, Option<u32>), MonochoraError> {
+    if renders_images(args) {
+        let target_gif_width = args.width.unwrap_or(gif_width);
+        let target_gif_height = args.height.unwrap_or(gif_height);
+        
+        // Columns are counted at the advance glyphs are actually drawn with (--char-width-ratio included),
+        // so the last one isn't clipped.
+        let (char_width_pixels, char_height_pixels) = rendered_cell_size(&build_gif_output_options(args))?;
+        
+        let chars_width = (target_gif_width as f32 / char_width_pixels) as u32;
+        let chars_height = (target_gif_height as f32 / char_height_pixels) as u32;
+        
+        if chars_width == 0 || chars_height == 0 {
+            return Err(MonochoraError::InvalidDimensions { 
+                width: chars_width, 
+                height: chars_height 
+            });
+        }
+        
+        Ok((Some(chars_width), Some(chars_height)))
+    } else {
+        let terminal_width = if args.fit_terminal && args.gif_output.is_none() && !args.save {
+            match get_terminal_size() {
+                Ok((w, _)) => Some(w),
+                Err(e) => {
+                    warn!("Failed to get terminal size: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        
+        Ok((args.width.or(terminal_width), args.height))
+    }
 }
 
-fn list_available_charsets() {
-    println!("Available Character Sets:\n");
+fn generate_default_output_path(input: &str) -> PathBuf {
+    if input.starts_with("http") {
+        PathBuf::from("downloaded_gif_ascii.txt")
+    } else {
+        let input_path = PathBuf::from(input);
+        match input_path.file_stem() {
+            Some(stem) => {
+                let mut name = stem.to_os_string();
+                name.push("_ascii.txt");
+                PathBuf::from(name)
+            }
+            None => PathBuf::from("output_ascii.txt")
+        }
+    }
+}
+
+// First of path, path_1, path_2, ... (counter before the extension) that does not exist yet.
+fn unique_output_path(path: PathBuf) -> PathBuf {
+    if !is_existing_output_file(&path) {
+        return path;
+    }
+
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|ext| ext.to_string_lossy().into_owned());
+
+    (1u32..)
+        .map(|counter| {
+            let name = match &extension {
+                Some(ext) => format!("{}_{}.{}", stem, counter, ext),
+                None => format!("{}_{}", stem, counter),
+            };
+            path.with_file_name(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(path)
+}
+
+fn generate_gif_output_path(input: &str, gif_output: &Option<Option<PathBuf>>) -> PathBuf {
+    match gif_output {
+        Some(Some(path)) => {
+            if path.extension().is_none() && !is_named_pipe(path) {
+                path.with_extension("gif")
+            } else {
+                path.clone()
+            }
+        }
+        Some(None) => {
+            if input.starts_with("http") {
+                PathBuf::from("ascii_downloaded.gif")
+            } else {
+                let input_path = PathBuf::from(input);
+                match input_path.file_stem() {
+                    Some(stem) => {
+                        let mut name = String::from("ascii_");
+                        name.push_str(&stem.to_string_lossy());
+                        name.push_str(".gif");
+                        PathBuf::from(name)
+                    }
+                    None => PathBuf::from("ascii_output.gif")
+                }
+            }
+        }
+        None => unreachable!("This function should only be called when gif_output is Some"),
+    }
+}
+
+// --fps-auto picks the rate of the typical frame, so a few held or jittery delays don't set the pace.
+fn resolve_target_fps(args: &Args, median_delay_ms: u16) -> Option<f32> {
+    if args.fps_auto {
+        Some(1000.0 / median_delay_ms.max(1) as f32)
+    } else {
+        args.fps
+    }
+}
+
+fn calculate_adjusted_frame_delays(
+    original_delays: &[u16],
+    speed: Option<f32>,
+    fps: Option<f32>,
+    quiet: bool
+) -> Vec<u16> {
+    if !quiet {
+        if let Some(target_fps) = fps {
+            info!("Setting consistent frame rate to {:.1} FPS ({} ms per frame)", target_fps, (1000.0 / target_fps) as u16);
+        } else if let Some(speed_mult) = speed {
+            info!("Adjusting animation speed by {:.2}x", speed_mult);
+        }
+    }
+
+    adjust_frame_delays(original_delays, speed, fps)
+}
+
+fn apply_playback_order<T: Clone>(items: Vec<T>, reverse: bool, boomerang: bool) -> Vec<T> {
+    let mut ordered = items;
+
+    if reverse {
+        ordered.reverse();
+    }
+
+    if boomerang && ordered.len() > 2 {
+        let backward: Vec<T> = ordered[1..ordered.len() - 1].iter().rev().cloned().collect();
+        ordered.extend(backward);
+    }
+
+    ordered
+}
+
+fn select_time_window(
+    gif_data: GifData,
+    since_ms: u64,
+    duration_ms: Option<u64>,
+) -> Result<GifData, MonochoraError> {
+    let total_ms = gif_data.total_duration_ms();
+    if since_ms >= total_ms {
+        return Err(MonochoraError::Config(format!(
+            "Start time {} ms is past the end of the animation ({} ms)", since_ms, total_ms
+        )));
+    }
+
+    let end_ms = match duration_ms {
+        Some(duration) if since_ms + duration > total_ms => {
+            return Err(MonochoraError::Config(format!(
+                "Time window {}-{} ms extends past the end of the animation ({} ms)",
+                since_ms, since_ms + duration, total_ms
+            )));
+        }
+        Some(duration) => since_ms + duration,
+        None => total_ms,
+    };
+
+    // Keep every frame that is on screen for any part of [since_ms, end_ms).
+    let mut frame_start = 0u64;
+    let frames: Vec<_> = gif_data.frames
+        .into_iter()
+        .filter(|frame| {
+            let start = frame_start;
+            frame_start += frame.delay_time_ms as u64;
+            start < end_ms && frame_start > since_ms
+        })
+        .collect();
+
+    Ok(GifData { frames, ..gif_data })
+}
+
+const AUTO_INVERT_THRESHOLD: f32 = 0.5;
+
+fn mean_frame_brightness(gif_data: &GifData, config: &AsciiConverterConfig) -> Option<f32> {
+    let per_frame: Vec<f32> = gif_data.frames
+        .par_iter()
+        .filter_map(|frame| average_brightness(&frame.image, config))
+        .collect();
+
+    (!per_frame.is_empty()).then(|| per_frame.iter().sum::<f32>() / per_frame.len() as f32)
+}
+
+// --auto-invert: a mostly dark source is inverted; returns the brightness the choice was based on.
+fn apply_auto_invert(gif_data: &GifData, config: &mut AsciiConverterConfig) -> Option<f32> {
+    let brightness = mean_frame_brightness(gif_data, config)?;
+    config.invert = brightness < AUTO_INVERT_THRESHOLD;
+    Some(brightness)
+}
+
+// The renderer fills anything without characters with the background color.
+fn append_repeat_gap(frames: &mut Vec<Vec<String>>, delays: &mut Vec<u16>, gap_ms: u16) {
+    let line_count = frames.iter().map(Vec::len).max().unwrap_or(0);
+    frames.push(vec![String::new(); line_count]);
+    delays.push(gap_ms);
+}
+
+fn dedup_frames(frames: Vec<Vec<String>>, delays: Vec<u16>) -> (Vec<Vec<String>>, Vec<u16>) {
+    let mut merged_frames: Vec<Vec<String>> = Vec::with_capacity(frames.len());
+    let mut merged_delays: Vec<u16> = Vec::with_capacity(delays.len());
+    let mut last_hash = None;
+
+    for (frame, delay) in frames.into_iter().zip(delays) {
+        let mut hasher = DefaultHasher::new();
+        frame.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        if last_hash == Some(hash)
+            && let (Some(last_frame), Some(last_delay)) = (merged_frames.last(), merged_delays.last_mut())
+            && *last_frame == frame
+            // Keep a separate frame rather than saturating so the total duration is unchanged.
+            && let Some(sum) = last_delay.checked_add(delay) {
+            *last_delay = sum;
+            continue;
+        }
+
+        last_hash = Some(hash);
+        merged_frames.push(frame);
+        merged_delays.push(delay);
+    }
+
+    (merged_frames, merged_delays)
+}
+
+async fn process_ascii_conversion(
+    args: &Args,
+    gif_data: &GifData,
+    config: &AsciiConverterConfig,
+    pool: &ThreadPool,
+) -> Result<(Vec<Vec<String>>, Vec<u16>), MonochoraError> {
+    if !args.quiet {
+        info!("Converting {} frames to ASCII...", gif_data.frames.len());
+    }
     
-    println!("Built-in Sets:");
-    println!("  simple:   {}", " .:-=+*#%@");
-    println!("  detailed: {}", " .'`^\",:;Il!i><~+_-?][}{1)(|\\/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@");
+    let start_time = std::time::Instant::now();
     
-    println!("\nExample Custom Sets:");
-    println!("  density:    \" .-+*#%@@\"");
-    println!("  minimal:    \" .oO@\"");
-    println!("  technical:  \" .-=+*#\"");
-    println!("  artistic:   \" ·∘○●◉\"");
-    println!("  japanese:   \"・〆ヲァィヵヶ\"");
-    println!("  blocks:     \" ░▒▓█\"");
+    let ascii_frames = pool.install(|| convert_frames(gif_data, config, args.colored))?;
+    let original_delays: Vec<u16> = gif_data.frames.iter().map(|frame| frame.delay_time_ms).collect();
+    let ascii_frames = apply_playback_order(ascii_frames, args.reverse, args.boomerang);
+    let original_delays = apply_playback_order(original_delays, args.reverse, args.boomerang);
+
+    let (ascii_frames, original_delays) = if args.dedup_frames {
+        let frame_count = ascii_frames.len();
+        let (frames, delays) = dedup_frames(ascii_frames, original_delays);
+        if !args.quiet {
+            info!("Merged {} duplicate frames ({} -> {})", frame_count - frames.len(), frame_count, frames.len());
+        }
+        (frames, delays)
+    } else {
+        (ascii_frames, original_delays)
+    };
     
-    println!("\nUsage:");
-    println!("  --charset \" .oO@\"              # Inline character set");
-    println!("  --charset-file ./my-chars.txt  # Load from file");
-    println!("  --simple                       # Use simple built-in set");
-    println!("  (default)                      # Use detailed built-in set");
+    let adjusted_delays = calculate_adjusted_frame_delays(
+        &original_delays,
+        args.speed,
+        resolve_target_fps(args, gif_data.delay_stats().median_ms),
+        args.quiet
+    );
     
-    println!("\nCharacter Set Rules:");
-    println!("  • Order from darkest to lightest");
-    println!("  • Minimum 2 characters, maximum 256");
-    println!("  • Must contain unique characters");
-    println!("  • No control characters (except tab/newline in files)");
+    let conversion_time = start_time.elapsed();
+    if !args.quiet {
+        info!("ASCII conversion completed in {:.2}s", conversion_time.as_secs_f64());
+    }
+
+    Ok((ascii_frames, adjusted_delays))
 }
 
-fn get_custom_charset(args: &Args) -> Result<Option<Vec<char>>, MonochoraError> {
-    if let Some(charset_string) = &args.charset {
-        return Ok(Some(charset_string.chars().collect()));
+fn build_gif_output_options(args: &Args) -> AsciiGifOutputOptions {
+    let mut options = AsciiGifOutputOptions {
+        font_size: args.font_size,
+        colored: args.colored,
+        substitute_missing: args.substitute_missing,
+        fallback_fonts: args.font_files.clone(),
+        max_colors: args.max_colors.unwrap_or(256),
+        optimize_frames: args.optimize_gif,
+        comment: args.gif_comment.clone(),
+        transparent: args.transparent,
+        gray_levels: args.gray_levels.unwrap_or(AsciiGifOutputOptions::default().gray_levels),
+        quantize: args.quantize.as_deref().and_then(|name| QuantizeMethod::parse(name).ok()).unwrap_or_default(),
+        layout: TextLayout::parse(&args.layout).unwrap_or_default(),
+        char_width_ratio: args.char_width_ratio,
+        global_palette: args.global_palette,
+        supersample: args.supersample.unwrap_or(1),
+        ..Default::default()
+    };
+    
+    if args.black_on_white {
+        options.bg_color = image::Rgb([255, 255, 255]); 
+        options.text_color = image::Rgb([0, 0, 0]);     
+    } else if args.white_on_black {
+        options.bg_color = image::Rgb([0, 0, 0]);       
+        options.text_color = image::Rgb([255, 255, 255]); 
+    }
+
+    if let Some(text) = &args.caption {
+        options.caption = Some(Caption {
+            text: text.clone(),
+            position: CaptionPosition::parse(&args.caption_position).unwrap_or_default(),
+            color: args.caption_color.as_deref()
+                .and_then(|color| parse_rgb_color(color).ok())
+                .unwrap_or(options.text_color),
+        });
+    }
+
+    options
+}
+
+#[derive(Serialize)]
+struct EffectiveConfig<'a> {
+    input: &'a str,
+    source_width: u32,
+    source_height: u32,
+    frame_count: usize,
+    charset_length: usize,
+    converter: &'a AsciiConverterConfig,
+    gif_output: Option<AsciiGifOutputOptions>,
+}
+
+fn print_effective_config(
+    args: &Args,
+    gif_data: &GifData,
+    config: &AsciiConverterConfig,
+) -> Result<(), MonochoraError> {
+    let effective = EffectiveConfig {
+        input: args.input.as_deref().unwrap_or_default(),
+        source_width: gif_data.width,
+        source_height: gif_data.height,
+        frame_count: gif_data.frames.len(),
+        charset_length: config.get_charset().len(),
+        converter: config,
+        gif_output: writes_gif(args).then(|| build_gif_output_options(args)),
+    };
+
+    let json = serde_json::to_string_pretty(&effective)
+        .map_err(|e| MonochoraError::Config(format!("Failed to serialize configuration: {}", e)))?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ProbeReport {
+    width: u32,
+    height: u32,
+    frames: usize,
+    loop_count: u16,
+    total_duration_ms: u64,
+    avg_delay: u16,
+    interlaced: bool,
+    has_transparency: bool,
+}
+
+// --keep-download copies the download out before the temporary file goes away.
+async fn fetch_input(args: &Args, input: &str) -> Result<InputPath, MonochoraError> {
+    let input_path = get_input_path(input).await?;
+
+    if input_path.is_download()
+        && let Some(keep_path) = &args.keep_download {
+        // A fresh file rather than fs::copy, which would carry over the temporary file's owner-only mode.
+        let mut kept = std::fs::File::create(keep_path).map_err(MonochoraError::Io)?;
+        std::io::copy(&mut std::fs::File::open(&input_path).map_err(MonochoraError::Io)?, &mut kept)
+            .map_err(MonochoraError::Io)?;
+        if !args.quiet {
+            info!("Saved downloaded GIF to {}", keep_path.display());
+        }
+    }
+
+    Ok(input_path)
+}
+
+async fn probe_input(args: &Args) -> Result<(), MonochoraError> {
+    let input_path = fetch_input(args, args.input.as_ref().unwrap()).await?;
+    let meta = probe_gif(&input_path)?;
+
+    let report = ProbeReport {
+        width: meta.width,
+        height: meta.height,
+        frames: meta.frame_count,
+        loop_count: meta.loop_count,
+        total_duration_ms: meta.total_duration_ms,
+        avg_delay: meta.average_frame_delay(),
+        interlaced: meta.interlaced,
+        has_transparency: meta.has_transparency,
+    };
+
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| MonochoraError::Config(format!("Failed to serialize probe report: {}", e)))?;
+    println!("{}", json);
+    Ok(())
+}
+
+const HISTOGRAM_MAX_BUCKETS: usize = 10;
+const HISTOGRAM_BAR_WIDTH: usize = 40;
+
+// Buckets are (lower_ms, upper_ms, count); distinct delays get their own bucket when there are few of them.
+fn bucket_delays(delays: &[u16]) -> Vec<(u16, u16, usize)> {
+    let mut distinct: Vec<u16> = delays.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    if distinct.len() <= HISTOGRAM_MAX_BUCKETS {
+        return distinct
+            .into_iter()
+            .map(|delay| (delay, delay, delays.iter().filter(|&&d| d == delay).count()))
+            .collect();
+    }
+
+    let min = distinct[0] as u32;
+    let max = distinct[distinct.len() - 1] as u32;
+    let width = (max - min).div_ceil(HISTOGRAM_MAX_BUCKETS as u32).max(1);
+    let mut buckets: Vec<(u16, u16, usize)> = (0..HISTOGRAM_MAX_BUCKETS as u32)
+        .map(|i| ((min + i * width) as u16, (min + (i + 1) * width - 1).min(max) as u16, 0))
+        .collect();
+
+    for &delay in delays {
+        let idx = ((delay as u32 - min) / width) as usize;
+        buckets[idx.min(HISTOGRAM_MAX_BUCKETS - 1)].2 += 1;
+    }
+
+    buckets.retain(|bucket| bucket.2 > 0);
+    buckets
+}
+
+fn print_timing_stats(gif_data: &GifData) {
+    let stats = gif_data.delay_stats();
+    let delays: Vec<u16> = gif_data.frames.iter().map(|frame| frame.delay_time_ms).collect();
+
+    println!("Frames: {}, total duration: {} ms", delays.len(), stats.total_ms);
+    println!(
+        "Delay (ms): min {}, median {}, average {}, max {}",
+        stats.min_ms, stats.median_ms, stats.average_ms, stats.max_ms
+    );
+    println!();
+
+    let buckets = bucket_delays(&delays);
+    let largest = buckets.iter().map(|bucket| bucket.2).max().unwrap_or(0).max(1);
+    for (lower, upper, count) in buckets {
+        let label = if lower == upper {
+            format!("{} ms", lower)
+        } else {
+            format!("{}-{} ms", lower, upper)
+        };
+        let bar = "#".repeat((count * HISTOGRAM_BAR_WIDTH).div_ceil(largest));
+        println!("{:>13} | {:<width$} {}", label, bar, count, width = HISTOGRAM_BAR_WIDTH);
+    }
+
+    if stats.min_ms.saturating_mul(2) < stats.max_ms {
+        println!();
+        println!("Frame delays vary widely; --fps can normalize them to a steady rate.");
+    }
+}
+
+async fn handle_gif_output(
+    args: &Args,
+    output_path: &Path,
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    loop_count: u16,
+    target_dimensions: Option<(u32, u32)>,
+    pool: &ThreadPool,
+) -> Result<(), MonochoraError> {
+    if !args.quiet {
+        info!("Generating ASCII GIF animation: {}", output_path.display());
+    }
+    
+    let gif_start = std::time::Instant::now();
+    
+    let mut options = build_gif_output_options(args);
+    // Text loaded with --from-text keeps whatever color escapes it was saved with.
+    if args.from_text.is_some() {
+        options.colored = ascii_frames.iter().flatten().any(|line| line.contains('\x1b'));
+    }
+    
+    if let Some(size) = &args.max_output_bytes {
+        let max_bytes = parse_byte_size(size)?;
+        let fit = pool.install(|| ascii_frames_to_gif_within_size(
+            ascii_frames,
+            frame_delays,
+            loop_count,
+            output_path,
+            &options,
+            target_dimensions,
+            max_bytes,
+        )).map_err(|e| MonochoraError::Animation(e.to_string()))?;
+
+        if !args.quiet {
+            let dimensions = fit.dimensions
+                .map(|(width, height)| format!("{}x{}, ", width, height))
+                .unwrap_or_default();
+            info!(
+                "{} {} bytes after {} attempt(s): {}font size {:.1}, palette {}, {} frames",
+                if fit.fits { "Fit in" } else { "Still over the limit at" },
+                fit.bytes, fit.attempts, dimensions, fit.font_size, fit.palette_size, fit.frame_count
+            );
+        }
+    } else {
+        pool.install(|| ascii_frames_to_gif_with_dimensions(
+            ascii_frames, 
+            frame_delays, 
+            loop_count, 
+            output_path, 
+            &options,
+            target_dimensions
+        )).map_err(|e| MonochoraError::Animation(e.to_string()))?;
+    }
+    
+    let gif_time = gif_start.elapsed();
+    if !args.quiet {
+        info!("GIF generation completed in {:.2}s", gif_time.as_secs_f64());
+    }
+    
+    if !args.quiet {
+        println!("Done! Output saved to: {}", output_path.display());
+    }
+    Ok(())
+}
+
+fn prepare_text_frames<'a>(args: &Args, ascii_frames: &'a [Vec<String>]) -> Cow<'a, [Vec<String>]> {
+    let mut frames = Cow::Borrowed(ascii_frames);
+
+    if args.strip_color {
+        frames = Cow::Owned(strip_frame_colors(&frames));
+    }
+
+    if args.trim_lines {
+        frames = Cow::Owned(trim_frame_lines(&frames));
+    }
+
+    if let Some(replacement) = args.space_char {
+        frames = Cow::Owned(substitute_frame_spaces(&frames, replacement));
+    }
+
+    if args.gutter {
+        frames = Cow::Owned(number_frame_lines(&frames));
+    }
+
+    frames
+}
+
+async fn handle_text_output(
+    args: &Args,
+    output_path: &Path,
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    separators: bool,
+    pool: &ThreadPool,
+) -> Result<(), MonochoraError> {
+    if !args.quiet {
+        info!("Saving ASCII animation: {}", output_path.display());
+    }
+    
+    let save_start = std::time::Instant::now();
+    
+    let file_options = TextFileOptions {
+        separators,
+        crlf: args.crlf,
+        rle: args.rle_text,
+        delays: if args.text_delays { frame_delays.to_vec() } else { Vec::new() },
+    };
+    pool.install(|| save_ascii_to_file_with_options(&prepare_text_frames(args, ascii_frames), output_path, &file_options))?;
+    
+    let save_time = save_start.elapsed();
+    if !args.quiet {
+        info!("File save completed in {:.2}s", save_time.as_secs_f64());
     }
     
-    if let Some(charset_file) = &args.charset_file {
-        let charset_string = load_charset_from_file(charset_file)?;
-        return Ok(Some(charset_string.chars().collect()));
+    if !args.quiet {
+        println!("Done! Output saved to: {}", output_path.display());
     }
-    
-    Ok(None)
+    Ok(())
 }
 
-fn setup_logging(level: &str) -> Result<(), MonochoraError> {
-    let filter = match level.to_lowercase().as_str() {
-        "error" => "error",
-        "warn" => "warn", 
-        "info" => "info",
-        "debug" => "debug",
-        "trace" => "trace",
-        _ => "info",
+#[derive(Serialize)]
+struct JsonAnimation<'a> {
+    frames: &'a [Vec<String>],
+    delays: &'a [u16],
+    width: u32,
+    height: u32,
+    loop_count: u16,
+}
+
+async fn handle_json_output(
+    args: &Args,
+    output_path: &Path,
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    gif_data: &GifData,
+) -> Result<(), MonochoraError> {
+    if !args.quiet {
+        info!("Saving ASCII animation as JSON: {}", output_path.display());
+    }
+
+    let animation = JsonAnimation {
+        frames: ascii_frames,
+        delays: frame_delays,
+        width: gif_data.width,
+        height: gif_data.height,
+        loop_count: gif_data.loop_count,
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .init();
+    let file = std::fs::File::create(output_path)?;
+    serde_json::to_writer(std::io::BufWriter::new(file), &animation)
+        .map_err(|e| MonochoraError::Config(format!("Failed to write JSON output: {}", e)))?;
 
+    if !args.quiet {
+        println!("Done! Output saved to: {}", output_path.display());
+    }
     Ok(())
 }
 
-fn setup_thread_pool(thread_count: Option<usize>, quiet: bool) -> Result<(), MonochoraError> {
-    if let Some(threads) = thread_count {
-        rayon::ThreadPoolBuilder::new()
-            .num_threads(threads)
-            .build_global()
-            .map_err(|e| MonochoraError::ThreadPool(e.to_string()))?;
-        
-        if !quiet {
-            info!("Using {} threads for parallel processing", threads);
-        }
+async fn handle_frames_dir_output(
+    args: &Args,
+    ascii_frames: &[Vec<String>],
+    gif_data: &GifData,
+    pool: &ThreadPool,
+) -> Result<(), MonochoraError> {
+    let output_dir = args.frames_dir.as_ref().unwrap();
+
+    if !args.quiet {
+        info!("Writing {} frames to: {}", ascii_frames.len(), output_dir.display());
     }
-    Ok(())
-}
 
-fn calculate_gif_dimensions(
-    args: &Args, 
-    gif_width: u32, 
-    gif_height: u32
-) -> Result<(Option<u32>, Option<u32>), MonochoraError> {
-    if args.gif_output.is_some() {
-        let target_gif_width = args.width.unwrap_or(gif_width);
-        let target_gif_height = args.height.unwrap_or(gif_height);
-        
-        let char_width_pixels = args.font_size * 0.5; 
-        let char_height_pixels = args.font_size;
-        
-        if char_width_pixels <= 0.0 || char_height_pixels <= 0.0 {
-            return Err(MonochoraError::InvalidFontSize { size: args.font_size });
-        }
-        
-        let chars_width = (target_gif_width as f32 / char_width_pixels) as u32;
-        let chars_height = (target_gif_height as f32 / char_height_pixels) as u32;
-        
-        if chars_width == 0 || chars_height == 0 {
-            return Err(MonochoraError::InvalidDimensions { 
-                width: chars_width, 
-                height: chars_height 
-            });
-        }
-        
-        Ok((Some(chars_width), Some(chars_height)))
+    let save_start = std::time::Instant::now();
+
+    let written = if args.frames_format == "png" {
+        let options = build_gif_output_options(args);
+        let target_dimensions = Some((
+            args.width.unwrap_or(gif_data.width),
+            args.height.unwrap_or(gif_data.height)
+        ));
+
+        pool.install(|| ascii_frames_to_png_files(ascii_frames, output_dir, &options, target_dimensions))
+            .map_err(|e| MonochoraError::Animation(e.to_string()))?
     } else {
-        let terminal_width = if args.fit_terminal && args.gif_output.is_none() && !args.save {
-            match get_terminal_size() {
-                Ok((w, _)) => Some(w),
-                Err(e) => {
-                    warn!("Failed to get terminal size: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        
-        Ok((args.width.or(terminal_width), args.height))
+        pool.install(|| save_ascii_frames_to_dir(&prepare_text_frames(args, ascii_frames), output_dir))?
+    };
+
+    let save_time = save_start.elapsed();
+    if !args.quiet {
+        info!("Frame export completed in {:.2}s", save_time.as_secs_f64());
     }
-}
 
-fn generate_default_output_path(input: &str) -> PathBuf {
-    if input.starts_with("http") {
-        PathBuf::from("downloaded_gif_ascii.txt")
-    } else {
-        let input_path = PathBuf::from(input);
-        match input_path.file_stem() {
-            Some(stem) => {
-                let mut name = stem.to_os_string();
-                name.push("_ascii.txt");
-                PathBuf::from(name)
-            }
-            None => PathBuf::from("output_ascii.txt")
-        }
+    if !args.quiet {
+        println!("Done! {} frames saved to: {}", written.len(), output_dir.display());
     }
+    Ok(())
 }
 
-fn generate_gif_output_path(input: &str, gif_output: &Option<Option<PathBuf>>) -> PathBuf {
-    match gif_output {
-        Some(Some(path)) => {
-            if path.extension().is_none() {
-                path.with_extension("gif")
-            } else {
-                path.clone()
-            }
-        }
-        Some(None) => {
-            if input.starts_with("http") {
-                PathBuf::from("ascii_downloaded.gif")
-            } else {
-                let input_path = PathBuf::from(input);
-                match input_path.file_stem() {
-                    Some(stem) => {
-                        let mut name = String::from("ascii_");
-                        name.push_str(&stem.to_string_lossy());
-                        name.push_str(".gif");
-                        PathBuf::from(name)
-                    }
-                    None => PathBuf::from("ascii_output.gif")
-                }
-            }
-        }
-        None => unreachable!("This function should only be called when gif_output is Some"),
+async fn handle_image_frames_dir_output(
+    args: &Args,
+    output_dir: &Path,
+    ascii_frames: &[Vec<String>],
+    gif_data: &GifData,
+    pool: &ThreadPool,
+) -> Result<(), MonochoraError> {
+    if !args.quiet {
+        info!("Rendering {} frames as {} images to: {}", ascii_frames.len(), args.image_format, output_dir.display());
+    }
+
+    let render_start = std::time::Instant::now();
+
+    let options = build_gif_output_options(args);
+    let target_dimensions = Some((
+        args.width.unwrap_or(gif_data.width),
+        args.height.unwrap_or(gif_data.height)
+    ));
+
+    let written = pool.install(|| ascii_frames_to_image_files(ascii_frames, output_dir, &options, target_dimensions, &args.image_format))
+        .map_err(|e| MonochoraError::Animation(e.to_string()))?;
+
+    let render_time = render_start.elapsed();
+    if !args.quiet {
+        info!("Frame rendering completed in {:.2}s", render_time.as_secs_f64());
+    }
+
+    if !args.quiet {
+        println!("Done! {} frames saved to: {}", written.len(), output_dir.display());
     }
+    Ok(())
 }
 
-fn calculate_adjusted_frame_delays(
-    original_delays: &[u16],
-    speed: Option<f32>,
-    fps: Option<f32>,
-    quiet: bool
-) -> Vec<u16> {
-    let adjusted_delays = if let Some(target_fps) = fps {
-        let target_delay_ms = (1000.0 / target_fps) as u16;
-        if !quiet {
-            info!("Setting consistent frame rate to {:.1} FPS ({} ms per frame)", target_fps, target_delay_ms);
-        }
-        vec![target_delay_ms; original_delays.len()]
-    } else if let Some(speed_mult) = speed {
-        if !quiet {
-            info!("Adjusting animation speed by {:.2}x", speed_mult);
-        }
-        original_delays.iter()
-            .map(|&delay| {
-                let adjusted = (delay as f32 / speed_mult) as u16;
-                adjusted.max(1)
-            })
-            .collect()
-    } else {
-        original_delays.to_vec()
-    };
+const CLIPBOARD_UNAVAILABLE: &str = "Clipboard output (--clipboard) requires monochora to be built with the `clipboard` feature";
 
-    adjusted_delays
+// Escape sequences are meaningless once pasted, so the clipboard always gets plain text.
+#[cfg(feature = "clipboard")]
+fn clipboard_text(frame: &[String]) -> String {
+    frame.iter().map(|line| monochora::display::strip_ansi(line)).collect::<Vec<_>>().join("\n")
 }
 
-async fn process_ascii_conversion(
-    args: &Args,
-    gif_data: &monochora::handler::GifData,
-    config: &AsciiConverterConfig,
-) -> Result<(Vec<Vec<String>>, Vec<u16>), MonochoraError> {
-    if !args.quiet {
-        info!("Converting {} frames to ASCII...", gif_data.frames.len());
+#[cfg(feature = "clipboard")]
+async fn handle_clipboard_output(args: &Args, ascii_frames: &[Vec<String>]) -> Result<(), MonochoraError> {
+    let frame = ascii_frames.first()
+        .ok_or_else(|| MonochoraError::Animation("No frames to copy".to_string()))?;
+
+    if ascii_frames.len() > 1 && !args.quiet {
+        warn!("Copying only the first of {} frames; use --since to pick a different one", ascii_frames.len());
     }
-    
-    let start_time = std::time::Instant::now();
-    
-    let results: Vec<Result<(Vec<String>, u16), MonochoraError>> = gif_data.frames
-        .par_iter()
-        .map(|frame| {
-            let ascii_frame = if args.colored {
-                image_to_colored_ascii(&frame.image, config)
-            } else {
-                image_to_ascii(&frame.image, config)
-            };
-            ascii_frame.map(|ascii| (ascii, frame.delay_time_ms))
-        })
-        .collect();
-    
-    let results: Result<Vec<(Vec<String>, u16)>, MonochoraError> = results.into_iter().collect();
-    let results = results?;
-    
-    let (ascii_frames, original_delays): (Vec<_>, Vec<_>) = results.into_iter().unzip();
-    
-    let adjusted_delays = calculate_adjusted_frame_delays(
-        &original_delays,
-        args.speed,
-        args.fps,
-        args.quiet
-    );
-    
-    let conversion_time = start_time.elapsed();
+
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| MonochoraError::Terminal(format!("System clipboard is unavailable (is a display server running?): {}", e)))?;
+    let mut text = clipboard_text(frame);
+    if let Some(replacement) = args.space_char {
+        text = text.replace(' ', &replacement.to_string());
+    }
+
+    clipboard.set_text(text)
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to copy to the clipboard: {}", e)))?;
+
     if !args.quiet {
-        info!("ASCII conversion completed in {:.2}s", conversion_time.as_secs_f64());
+        println!("Done! First frame copied to the clipboard");
     }
+    Ok(())
+}
 
-    Ok((ascii_frames, adjusted_delays))
+#[cfg(not(feature = "clipboard"))]
+async fn handle_clipboard_output(_args: &Args, _ascii_frames: &[Vec<String>]) -> Result<(), MonochoraError> {
+    Err(MonochoraError::Config(CLIPBOARD_UNAVAILABLE.to_string()))
 }
 
-async fn handle_gif_output(
+const WEBP_UNAVAILABLE: &str = "WebP output (--webp-output) requires monochora to be built with the `webp` feature";
+
+#[cfg(feature = "webp")]
+async fn handle_webp_output(
     args: &Args,
+    output_path: &Path,
     ascii_frames: &[Vec<String>],
     frame_delays: &[u16],
-    gif_data: &monochora::handler::GifData,
+    gif_data: &GifData,
+    pool: &ThreadPool,
 ) -> Result<(), MonochoraError> {
-    let input = args.input.as_ref().unwrap();
-    let output_path = generate_gif_output_path(input, &args.gif_output);
-    
     if !args.quiet {
-        info!("Generating ASCII GIF animation: {}", output_path.display());
-    }
-    
-    let gif_start = std::time::Instant::now();
-    
-    let mut options = AsciiGifOutputOptions::default();
-    options.font_size = args.font_size;
-    options.colored = args.colored; 
-    
-    if args.black_on_white {
-        options.bg_color = image::Rgb([255, 255, 255]); 
-        options.text_color = image::Rgb([0, 0, 0]);     
-    } else if args.white_on_black {
-        options.bg_color = image::Rgb([0, 0, 0]);       
-        options.text_color = image::Rgb([255, 255, 255]); 
+        info!("Generating ASCII WebP animation: {}", output_path.display());
     }
-    
+
+    let webp_start = std::time::Instant::now();
+
+    let options = build_gif_output_options(args);
     let target_dimensions = Some((
         args.width.unwrap_or(gif_data.width),
         args.height.unwrap_or(gif_data.height)
     ));
-    
-    ascii_frames_to_gif_with_dimensions(
-        ascii_frames, 
-        frame_delays, 
-        gif_data.loop_count, 
-        &output_path, 
+
+    pool.install(|| monochora::output::ascii_frames_to_webp(
+        ascii_frames,
+        frame_delays,
+        gif_data.loop_count,
+        output_path,
         &options,
         target_dimensions
-    ).map_err(|e| MonochoraError::Animation(e.to_string()))?;
-    
-    let gif_time = gif_start.elapsed();
+    )).map_err(|e| MonochoraError::Animation(e.to_string()))?;
+
+    let webp_time = webp_start.elapsed();
     if !args.quiet {
-        info!("GIF generation completed in {:.2}s", gif_time.as_secs_f64());
+        info!("WebP generation completed in {:.2}s", webp_time.as_secs_f64());
+    }
+
+    if !args.quiet {
+        println!("Done! Output saved to: {}", output_path.display());
     }
-    
-    println!("Done! Output saved to: {}", output_path.display());
     Ok(())
 }
 
-async fn handle_text_output(
+#[cfg(not(feature = "webp"))]
+async fn handle_webp_output(
+    _args: &Args,
+    _output_path: &Path,
+    _ascii_frames: &[Vec<String>],
+    _frame_delays: &[u16],
+    _gif_data: &GifData,
+    _pool: &ThreadPool,
+) -> Result<(), MonochoraError> {
+    Err(MonochoraError::Config(WEBP_UNAVAILABLE.to_string()))
+}
+
+async fn handle_sprite_sheet_output(
     args: &Args,
     ascii_frames: &[Vec<String>],
+    gif_data: &GifData,
+    pool: &ThreadPool,
 ) -> Result<(), MonochoraError> {
-    let input = args.input.as_ref().unwrap();
-    let output_path = args.output.clone().unwrap_or_else(|| {
-        generate_default_output_path(input)
-    });
-    
+    let output_path = args.sprite_sheet.as_ref().unwrap();
+    let columns = args.sprite_cols
+        .unwrap_or_else(|| (ascii_frames.len() as f64).sqrt().ceil() as u32);
+
     if !args.quiet {
-        info!("Saving ASCII animation: {}", output_path.display());
+        info!("Generating sprite sheet: {} ({} columns)", output_path.display(), columns);
+    }
+
+    let sheet_start = std::time::Instant::now();
+
+    let options = build_gif_output_options(args);
+    let target_dimensions = Some((
+        args.width.unwrap_or(gif_data.width),
+        args.height.unwrap_or(gif_data.height)
+    ));
+
+    pool.install(|| ascii_frames_to_sprite_sheet(ascii_frames, output_path, &options, target_dimensions, columns))
+        .map_err(|e| MonochoraError::Animation(e.to_string()))?;
+
+    let sheet_time = sheet_start.elapsed();
+    if !args.quiet {
+        info!("Sprite sheet generation completed in {:.2}s", sheet_time.as_secs_f64());
+    }
+
+    if !args.quiet {
+        println!("Done! Output saved to: {}", output_path.display());
+    }
+    Ok(())
+}
+
+struct BenchmarkStage {
+    name: &'static str,
+    samples: Vec<std::time::Duration>,
+}
+
+impl BenchmarkStage {
+    fn new(name: &'static str) -> Self {
+        Self { name, samples: Vec::new() }
+    }
+
+    fn sorted(&self) -> Vec<std::time::Duration> {
+        let mut samples = self.samples.clone();
+        samples.sort_unstable();
+        samples
+    }
+
+    fn median(&self) -> std::time::Duration {
+        let sorted = self.sorted();
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        }
+    }
+
+    fn print_row(&self) {
+        let sorted = self.sorted();
+        println!(
+            "{:<10} {:>10.2} {:>12.2} {:>10.2}",
+            self.name,
+            sorted[0].as_secs_f64() * 1000.0,
+            self.median().as_secs_f64() * 1000.0,
+            sorted[sorted.len() - 1].as_secs_f64() * 1000.0,
+        );
+    }
+}
+
+async fn run_benchmark(
+    args: &Args,
+    config: &AsciiConverterConfig,
+    runs: usize,
+    pool: &ThreadPool,
+) -> Result<(), MonochoraError> {
+    let input = args.input.as_ref().unwrap();
+    // Resolve once so URL inputs are only downloaded a single time.
+    let input_path = fetch_input(args, input).await?;
+    let render = renders_images(args);
+
+    let mut decode = BenchmarkStage::new("decode");
+    let mut convert = BenchmarkStage::new("convert");
+    let mut render_stage = BenchmarkStage::new("render");
+    let mut total = BenchmarkStage::new("total");
+    let mut frame_count = 0;
+
+    for run in 1..=runs {
+        let run_start = std::time::Instant::now();
+
+        let stage_start = std::time::Instant::now();
+        let gif_data = pool.install(|| decode_gif_with_limits(&input_path, &decode_limits(args)))?;
+        decode.samples.push(stage_start.elapsed());
+
+        let stage_start = std::time::Instant::now();
+        let ascii_frames = pool.install(|| convert_frames(&gif_data, config, args.colored))?;
+        convert.samples.push(stage_start.elapsed());
+
+        if render {
+            let scratch = tempfile::NamedTempFile::with_suffix(".gif")
+                .map_err(MonochoraError::Io)?;
+            let delays: Vec<u16> = gif_data.frames.iter().map(|frame| frame.delay_time_ms).collect();
+            let target_dimensions = Some((
+                args.width.unwrap_or(gif_data.width),
+                args.height.unwrap_or(gif_data.height)
+            ));
+
+            let stage_start = std::time::Instant::now();
+            pool.install(|| ascii_frames_to_gif_with_dimensions(
+                &ascii_frames,
+                &delays,
+                gif_data.loop_count,
+                scratch.path(),
+                &build_gif_output_options(args),
+                target_dimensions
+            ))?;
+            render_stage.samples.push(stage_start.elapsed());
+        }
+
+        total.samples.push(run_start.elapsed());
+        frame_count = ascii_frames.len();
+
+        if !args.quiet {
+            info!("Benchmark run {}/{} finished in {:.2}s", run, runs, run_start.elapsed().as_secs_f64());
+        }
     }
-    
-    let save_start = std::time::Instant::now();
-    
-    save_ascii_to_file(ascii_frames, &output_path)?;
-    
-    let save_time = save_start.elapsed();
-    if !args.quiet {
-        info!("File save completed in {:.2}s", save_time.as_secs_f64());
+
+    println!("Benchmark: {} runs, {} frames, {} threads", runs, frame_count, pool.current_num_threads());
+    println!("{:<10} {:>10} {:>12} {:>10}", "stage", "min (ms)", "median (ms)", "max (ms)");
+    decode.print_row();
+    convert.print_row();
+    if render {
+        render_stage.print_row();
     }
-    
-    println!("Done! Output saved to: {}", output_path.display());
+    total.print_row();
+
+    let median_total = total.median().as_secs_f64();
+    if median_total > 0.0 {
+        println!("Throughput: {:.1} frames/s (median)", frame_count as f64 / median_total);
+    }
+
     Ok(())
 }
 
+// Delays arrive already adjusted for --speed/--fps, so only the terminal-side settings are carried here.
+fn playback_options(args: &Args, loop_count: Option<u16>) -> PlaybackOptions {
+    PlaybackOptions {
+        loop_count,
+        clear_between_frames: !args.no_clear,
+        max_fps: args.max_terminal_fps,
+        log_dropped_frames: args.log_dropped_frames,
+        ..PlaybackOptions::default()
+    }
+}
+
 async fn handle_terminal_display(
     args: &Args,
     ascii_frames: &[Vec<String>],
@@ -591,24 +2748,28 @@ async fn handle_terminal_display(
         info!("Press 'q' or 'Esc' to exit the animation...");
     }
     
-    display_ascii_animation(ascii_frames, frame_delays, loop_count, true).await
+    display_ascii_animation(ascii_frames, frame_delays, &playback_options(args, Some(loop_count))).await
 }
 
 async fn handle_responsive_terminal_display(
     args: &Args,
     _initial_frames: &[Vec<String>],
     frame_delays: &[u16],
-    gif_data: &monochora::handler::GifData,
+    gif_data: &GifData,
     config: &AsciiConverterConfig,
+    pool: &Arc<ThreadPool>,
 ) -> Result<(), MonochoraError> {
     let initial_dims = TerminalDimensions::current()?;
+    let mut ordered_gif_data = gif_data.clone();
+    ordered_gif_data.frames = apply_playback_order(ordered_gif_data.frames, args.reverse, args.boomerang);
+
     let mut frame_manager = ResponsiveFrameManager::new(
-        gif_data.clone(),
+        ordered_gif_data,
         config.clone(),
         frame_delays.to_vec(),
         initial_dims,
         args.colored,
-    );
+    ).with_thread_pool(Arc::clone(pool));
 
     if args.watch_terminal {
         let mut watcher = TerminalWatcher::new()?;
@@ -618,46 +2779,54 @@ async fn handle_responsive_terminal_display(
         display_responsive_ascii_animation(&mut frame_manager, resize_rx, gif_data.loop_count).await
     } else {
         let frames = frame_manager.get_frames()?;
-        display_ascii_animation(frames, frame_delays, gif_data.loop_count, true).await
+        display_ascii_animation(frames, frame_delays, &playback_options(args, Some(gif_data.loop_count))).await
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
-
-    if args.list_charsets {
-        list_available_charsets();
-        return Ok(());
+// Splits `archive.zip#member.gif` (or a bare `archive.zip`) into the archive path and optional member name.
+fn split_archive_input(input: &str) -> Option<(&str, Option<&str>)> {
+    if input.starts_with("http://") || input.starts_with("https://") {
+        return None;
     }
 
-    if let Err(e) = setup_logging(&args.log_level) {
-        eprintln!("Warning: Failed to setup logging: {}", e);
-    }
+    let (path, member) = match input.split_once('#') {
+        Some((path, member)) => (path, Some(member).filter(|member| !member.is_empty())),
+        None => (input, None),
+    };
 
-    if let Err(e) = validate_args(&args) {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
-    }
+    path.to_ascii_lowercase().ends_with(".zip").then_some((path, member))
+}
 
-    if let Err(e) = setup_thread_pool(args.threads, args.quiet) {
-        error!("Failed to setup thread pool: {}", e);
-        return Err(e.into());
-    }
+const ARCHIVE_UNAVAILABLE: &str = "Reading GIFs from ZIP archives requires monochora to be built with the `archive` feature";
 
-    let input = args.input.as_ref().unwrap();
+#[cfg(feature = "archive")]
+fn decode_archive_input(archive_path: &str, member: Option<&str>, args: &Args, pool: &ThreadPool) -> Result<GifData, MonochoraError> {
+    pool.install(|| monochora::handler::decode_gif_from_archive_with_limits(archive_path, member, &decode_limits(args)))
+}
+
+#[cfg(not(feature = "archive"))]
+fn decode_archive_input(_archive_path: &str, _member: Option<&str>, _args: &Args, _pool: &ThreadPool) -> Result<GifData, MonochoraError> {
+    Err(MonochoraError::Config(ARCHIVE_UNAVAILABLE.to_string()))
+}
 
+async fn load_gif(input: &str, args: &Args, pool: &ThreadPool) -> Result<GifData, MonochoraError> {
     if !args.quiet {
         info!("Loading GIF: {}", input);
     }
     
-    let input_path = get_input_path(input).await
-        .map_err(|e| {
-            error!("Failed to get input path: {}", e);
-            e
-        })?;
-    
-    let gif_data = decode_gif(&input_path)
+    let decoded = if let Some((archive_path, member)) = split_archive_input(input) {
+        decode_archive_input(archive_path, member, args, pool)
+    } else {
+        let input_path = fetch_input(args, input).await
+            .map_err(|e| {
+                error!("Failed to get input path: {}", e);
+                e
+            })?;
+
+        pool.install(|| decode_gif_with_limits(&input_path, &decode_limits(args)))
+    };
+
+    let gif_data = decoded
         .map_err(|e| {
             error!("Failed to decode GIF: {}", e);
             e
@@ -673,9 +2842,244 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
-    let (ascii_width, ascii_height) = calculate_gif_dimensions(&args, gif_data.width, gif_data.height)?;
+    Ok(gif_data)
+}
+
+#[tokio::main]
+async fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if args.list_charsets {
+        list_available_charsets();
+        return;
+    }
+
+    if args.charset_preview {
+        if let Err(e) = print_charset_preview(&args) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(path) = &args.decode_rle {
+        if let Err(e) = print_rle_file(path) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Err(e) = setup_logging(effective_log_level(&args), &args.log_format) {
+        eprintln!("Warning: Failed to setup logging: {}", e);
+    }
+
+    if let Err(e) = validate_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    if writes_gif(&args) || writes_webp(&args) {
+        spawn_interrupt_cleanup();
+    }
+
+    if let Err(e) = run(&args).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+// Ctrl-C while an animation is being written deletes its unfinished ".part" file instead of
+// leaving a truncated GIF; the target path is only ever replaced by a complete file.
+fn spawn_interrupt_cleanup() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let removed = remove_partial_outputs();
+            eprintln!("Interrupted; removed {} partial output file(s)", removed);
+            std::process::exit(130);
+        }
+    });
+}
+
+async fn run(args: &Args) -> Result<(), MonochoraError> {
+    let pool = match setup_thread_pool(args.threads, args.quiet) {
+        Ok(pool) => pool,
+        Err(e) => {
+            error!("Failed to setup thread pool: {}", e);
+            return Err(e);
+        }
+    };
+
+    if args.probe {
+        return probe_input(args).await;
+    }
+
+    if writes_file_output(args) && !(args.stats || args.print_config || args.benchmark.is_some()) {
+        check_output_overwrite(args)?;
+    }
+
+    if let Some(path) = &args.from_text {
+        text_to_gif(args, path, &pool).await
+    } else if args.watch_file {
+        watch_input_file(args, &pool).await
+    } else if args.stream {
+        stream_input(args, &pool).await
+    } else {
+        convert_input(args, &pool).await
+    }
+}
+
+// Saved text carries no loop count, so the GIF loops forever; --speed and --fps still apply to the stored delays.
+async fn text_to_gif(args: &Args, text_path: &Path, pool: &ThreadPool) -> Result<(), MonochoraError> {
+    let (ascii_frames, stored_delays) = load_ascii_from_file(text_path)?;
+    if !args.quiet {
+        info!("Loaded {} frames from {}", ascii_frames.len(), text_path.display());
+    }
+
+    let fps = resolve_target_fps(args, median_delay(&stored_delays));
+    let frame_delays = calculate_adjusted_frame_delays(&stored_delays, args.speed, fps, args.quiet);
+    let sink = resolve_output_sink(args)?;
+    let OutputSink::Gif(path) = &sink else {
+        unreachable!("validate_args only allows --from-text with GIF output");
+    };
+    handle_gif_output(args, path, &ascii_frames, &frame_delays, 0, None, pool).await?;
+    print_output_path(args, &sink);
+    Ok(())
+}
+
+// Frames converted ahead of playback; bounds memory while decoding outpaces the display.
+const STREAM_BUFFER_FRAMES: usize = 16;
+
+async fn stream_input(args: &Args, pool: &Arc<ThreadPool>) -> Result<(), MonochoraError> {
+    let input = args.input.as_ref().unwrap();
+    let input_path = fetch_input(args, input).await?;
+    let file = std::fs::File::open(&input_path).map_err(MonochoraError::Io)?;
+    let stream = GifFrameStream::with_limits(std::io::BufReader::new(file), &decode_limits(args))?;
+    let config = build_converter_config(args, stream.width(), stream.height())?;
+
+    if !args.quiet {
+        if let Some(speed) = args.speed {
+            info!("Adjusting animation speed by {:.2}x", speed);
+        }
+        info!("Streaming playback; press 'q' or 'Esc' to exit the animation...");
+    }
+
+    let (frame_tx, frame_rx) = mpsc::channel(STREAM_BUFFER_FRAMES);
+    let colored = args.colored;
+    let (speed, fps) = (args.speed, args.fps);
+    let pool = Arc::clone(pool);
+
+    let producer = tokio::task::spawn_blocking(move || {
+        for frame in stream {
+            let converted = frame.and_then(|frame| {
+                let lines = pool.install(|| if colored {
+                    image_to_colored_ascii(&frame.image, &config)
+                } else {
+                    image_to_ascii(&frame.image, &config)
+                })?;
+                let delay = calculate_adjusted_frame_delays(&[frame.delay_time_ms], speed, fps, true)[0];
+                Ok((lines, delay))
+            });
+
+            let failed = converted.is_err();
+            // A closed channel means playback ended early, so there is no point decoding further.
+            if frame_tx.blocking_send(converted).is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    let result = display_streamed_ascii_animation(frame_rx, &playback_options(args, None)).await;
+    let _ = producer.await;
+    result
+}
+
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(250);
+
+async fn wait_for_change(changes: &mut mpsc::UnboundedReceiver<()>) -> Result<(), MonochoraError> {
+    changes.recv().await
+        .ok_or_else(|| MonochoraError::Config("File watcher stopped unexpectedly".to_string()))?;
+    debounce_changes(changes).await
+}
+
+// Editors often emit several events per save, so wait until the file has been quiet for a moment.
+async fn debounce_changes(changes: &mut mpsc::UnboundedReceiver<()>) -> Result<(), MonochoraError> {
+    loop {
+        match tokio::time::timeout(WATCH_DEBOUNCE, changes.recv()).await {
+            Ok(Some(())) => continue,
+            Ok(None) => return Err(MonochoraError::Config("File watcher stopped unexpectedly".to_string())),
+            Err(_) => return Ok(()),
+        }
+    }
+}
+
+async fn watch_input_file(args: &Args, pool: &Arc<ThreadPool>) -> Result<(), MonochoraError> {
+    let input = PathBuf::from(args.input.as_ref().unwrap());
+    let file_name = input.file_name().map(|name| name.to_os_string());
+    // Watch the directory, since editors commonly save by replacing the file.
+    let watch_dir = match input.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let (change_tx, mut changes) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && !event.kind.is_access()
+            && event.paths.iter().any(|path| path.file_name() == file_name.as_deref()) {
+            let _ = change_tx.send(());
+        }
+    }).map_err(|e| MonochoraError::Config(format!("Failed to start file watcher: {}", e)))?;
+
+    watcher.watch(&watch_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| MonochoraError::Config(format!("Failed to watch {}: {}", watch_dir.display(), e)))?;
+
+    if !args.quiet {
+        info!("Watching {} for changes (press Ctrl+C to stop)", input.display());
+    }
+
+    loop {
+        // Terminal display runs until interrupted, so a change cancels the current pass.
+        tokio::select! {
+            result = convert_input(args, pool) => {
+                if let Err(e) = result {
+                    error!("Conversion failed: {}", e);
+                }
+                wait_for_change(&mut changes).await?;
+            }
+            changed = changes.recv() => {
+                changed.ok_or_else(|| MonochoraError::Config("File watcher stopped unexpectedly".to_string()))?;
+                debounce_changes(&mut changes).await?;
+            }
+        }
+
+        if !args.quiet {
+            info!("{} changed, reloading", input.display());
+        }
+    }
+}
+
+// Source width/height ratio that maps onto the requested grid without distortion.
+fn center_crop_aspect(args: &Args, config: &AsciiConverterConfig) -> f32 {
+    let (width, height) = (args.width.unwrap_or(1) as f32, args.height.unwrap_or(1) as f32);
+    if renders_images(args) {
+        // Rendered output takes --width/--height in pixels.
+        width / height
+    } else {
+        width / (height * config.char_aspect)
+    }
+}
+
+fn build_converter_config(args: &Args, gif_width: u32, gif_height: u32) -> Result<AsciiConverterConfig, MonochoraError> {
+    let (ascii_width, ascii_height) = calculate_gif_dimensions(args, gif_width, gif_height)?;
 
-    let custom_charset = get_custom_charset(&args)?;
+    let custom_charset = get_custom_charset(args)?;
 
     let config = AsciiConverterConfig {
         width: ascii_width,
@@ -685,27 +3089,365 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         detailed: !args.simple,
         preserve_aspect_ratio: args.preserve_aspect,
         scale_factor: args.scale,
+        scale_x: args.scale_x,
+        scale_y: args.scale_y,
         custom_charset,
+        brightness: args.brightness,
+        contrast: args.contrast,
+        invert_colors: args.invert_colors,
+        channel_order: args.swap_channels.as_deref().map(parse_channel_order).transpose()?,
+        posterize_levels: args.posterize,
+        color_merge_threshold: args.color_threshold,
+        resample: ResampleFilter::parse(&args.resample)?,
+        color_depth: resolve_color_depth(args)?,
+        colormap: args.colormap.as_deref().map(Colormap::parse).transpose()?,
+        mark_transparent: args.transparent,
+        sample_offset: args.sample_offset.as_deref().map(parse_sample_offset).transpose()?.unwrap_or([0.0, 0.0]),
+        sample_jitter: args.sample_jitter.unwrap_or(0.0),
+        sample_seed: args.sample_seed,
+        area_luminance: args.area_luminance,
+        min_brightness: args.min_brightness,
+        max_brightness: args.max_brightness,
+        detail_aware: args.detail_aware,
     };
+    config.validate()?;
+    Ok(config)
+}
+
+async fn convert_input(args: &Args, pool: &Arc<ThreadPool>) -> Result<(), MonochoraError> {
+    let input = args.input.as_ref().unwrap();
+
+    let mut gif_data = load_gif(input, args, pool).await?;
+
+    if !args.concat.is_empty() {
+        let mut segments = vec![gif_data];
+        for extra_input in &args.concat {
+            segments.push(load_gif(extra_input, args, pool).await?);
+        }
+
+        let canvas = args.concat_canvas.as_deref().map(parse_canvas_size).transpose()?;
+        gif_data = pool.install(|| concat_gifs(segments, canvas))?;
 
-    if !args.quiet && config.custom_charset.is_some() {
-        info!("Using custom character set with {} characters", 
-            config.custom_charset.as_ref().unwrap().len());
+        if !args.quiet {
+            info!(
+                "Concatenated animation: {} frames, {}x{}",
+                gif_data.frames.len(),
+                gif_data.width,
+                gif_data.height
+            );
+        }
     }
 
-    let (ascii_frames, frame_delays) = process_ascii_conversion(&args, &gif_data, &config).await?;
+    if args.trim_loop_frame {
+        let (trimmed_gif, trimmed) = trim_loop_frame(gif_data);
+        gif_data = trimmed_gif;
 
-    if args.gif_output.is_some() {
-        handle_gif_output(&args, &ascii_frames, &frame_delays, &gif_data).await?;
-    } else if args.save || args.output.is_some() {
-        handle_text_output(&args, &ascii_frames).await?;
-    } else {
-        if args.responsive {
-            handle_responsive_terminal_display(&args, &ascii_frames, &frame_delays, &gif_data, &config).await?;
-        } else {
-            handle_terminal_display(&args, &ascii_frames, &frame_delays, gif_data.loop_count).await?;
+        if !args.quiet {
+            if trimmed {
+                info!("Dropped the final frame, a duplicate of the first; {} frames remain", gif_data.frames.len());
+            } else {
+                info!("Final frame differs from the first, nothing to trim");
+            }
+        }
+    }
+
+    if args.since.is_some() || args.duration.is_some() {
+        let since_ms = args.since.as_deref().map(parse_time_ms).transpose()?.unwrap_or(0);
+        let duration_ms = args.duration.as_deref().map(parse_time_ms).transpose()?;
+        let frame_count = gif_data.frames.len();
+        gif_data = select_time_window(gif_data, since_ms, duration_ms)?;
+
+        if !args.quiet {
+            info!("Selected {} of {} frames in the requested time window", gif_data.frames.len(), frame_count);
+        }
+    }
+
+    if let Some(target_fps) = args.target_fps_decimate {
+        let frame_count = gif_data.frames.len();
+        gif_data = decimate_frames(gif_data, target_fps)?;
+
+        if !args.quiet {
+            info!("Kept {} of {} frames for {:.2} FPS", gif_data.frames.len(), frame_count, target_fps);
+        }
+    }
+
+    if let Some(steps) = args.interpolate {
+        gif_data = pool.install(|| interpolate_frames(gif_data, steps, &decode_limits(args)))?;
+
+        if !args.quiet {
+            info!("Interpolated animation: {} frames", gif_data.frames.len());
+        }
+    }
+
+    if args.stats {
+        print_timing_stats(&gif_data);
+        return Ok(());
+    }
+
+    let mut config = build_converter_config(args, gif_data.width, gif_data.height)?;
+
+    if args.center_crop {
+        let (source_width, source_height) = (gif_data.width, gif_data.height);
+        gif_data = pool.install(|| center_crop_to_aspect(gif_data, center_crop_aspect(args, &config)))?;
+
+        if !args.quiet {
+            info!("Center-cropped frames from {}x{} to {}x{}", source_width, source_height, gif_data.width, gif_data.height);
+        }
+    }
+
+    if args.auto_invert
+        && let Some(brightness) = pool.install(|| apply_auto_invert(&gif_data, &mut config))
+        && !args.quiet {
+        info!(
+            "Average source brightness {:.2}, {} brightness",
+            brightness,
+            if config.invert { "inverting" } else { "not inverting" }
+        );
+    }
+
+    if args.tui {
+        match run_parameter_tweaker(&gif_data.frames[0].image, config.clone(), args.colored)? {
+            Some(tweaked) if renders_images(args) => {
+                // Rendered output sizes characters from the pixel dimensions, so keep those.
+                config = AsciiConverterConfig {
+                    width: config.width,
+                    height: config.height,
+                    scale_factor: config.scale_factor,
+                    scale_x: config.scale_x,
+                    scale_y: config.scale_y,
+                    ..tweaked
+                };
+            }
+            Some(tweaked) => config = tweaked,
+            None => return Ok(()),
+        }
+    }
+
+    if !args.quiet && let Some(charset) = &config.custom_charset {
+        info!("Using custom character set with {} characters", charset.len());
+    }
+
+    if args.print_config {
+        print_effective_config(args, &gif_data, &config)?;
+        return Ok(());
+    }
+
+    if let Some(runs) = args.benchmark {
+        run_benchmark(args, &config, runs, pool).await?;
+        return Ok(());
+    }
+
+    let (mut ascii_frames, mut frame_delays) = process_ascii_conversion(args, &gif_data, &config, pool).await?;
+
+    if args.pad_frames {
+        ascii_frames = pad_frame_lines(&ascii_frames);
+    }
+
+    // Rendered images lay out cells and draw the caption themselves (see build_gif_output_options).
+    let layout = TextLayout::parse(&args.layout)?;
+    if layout != TextLayout::Ltr && !renders_images(args) {
+        ascii_frames = layout_frame_lines(&ascii_frames, layout);
+    }
+
+    if let Some(caption) = &args.caption
+        && !renders_images(args) {
+        let position = CaptionPosition::parse(&args.caption_position)?;
+        ascii_frames = caption_frame_lines(&ascii_frames, caption, position);
+    }
+
+    if let Some(gap_ms) = args.repeat_gap {
+        if gif_data.loop_count == 1 {
+            return Err(MonochoraError::Config(
+                "Loop gap (--repeat-gap) requires a looping animation".to_string()
+            ));
+        }
+
+        append_repeat_gap(&mut ascii_frames, &mut frame_delays, gap_ms);
+        if !args.quiet {
+            info!("Added a {} ms blank frame between loops", gap_ms);
+        }
+    }
+
+    let sink = resolve_output_sink(args)?;
+    match &sink {
+        OutputSink::Gif(path) => {
+            let target_dimensions = Some((
+                args.width.unwrap_or(gif_data.width),
+                args.height.unwrap_or(gif_data.height)
+            ));
+            handle_gif_output(args, path, &ascii_frames, &frame_delays, gif_data.loop_count, target_dimensions, pool).await?;
+        }
+        OutputSink::Webp(path) => {
+            handle_webp_output(args, path, &ascii_frames, &frame_delays, &gif_data, pool).await?;
+        }
+        OutputSink::Clipboard => {
+            handle_clipboard_output(args, &ascii_frames).await?;
+        }
+        OutputSink::Text(path) => {
+            handle_text_output(args, path, &ascii_frames, &frame_delays, true, pool).await?;
+        }
+        OutputSink::Ansi(path) => {
+            handle_text_output(args, path, &ascii_frames, &frame_delays, false, pool).await?;
+        }
+        OutputSink::Json(path) => {
+            handle_json_output(args, path, &ascii_frames, &frame_delays, &gif_data).await?;
+        }
+        OutputSink::SpriteSheet => {
+            handle_sprite_sheet_output(args, &ascii_frames, &gif_data, pool).await?;
+        }
+        OutputSink::FramesDir => {
+            handle_frames_dir_output(args, &ascii_frames, &gif_data, pool).await?;
+        }
+        OutputSink::ImageFramesDir(dir) => {
+            handle_image_frames_dir_output(args, dir, &ascii_frames, &gif_data, pool).await?;
+        }
+        OutputSink::Terminal if args.responsive => {
+            handle_responsive_terminal_display(args, &ascii_frames, &frame_delays, &gif_data, &config, pool).await?;
+        }
+        OutputSink::Terminal => {
+            handle_terminal_display(args, &ascii_frames, &frame_delays, gif_data.loop_count).await?;
         }
     }
 
+    print_output_path(args, &sink);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(text: &str) -> Vec<String> {
+        vec![text.to_string(), text.to_string()]
+    }
+
+    #[test]
+    fn dedup_collapses_identical_frames_into_their_summed_delay() {
+        let frames = vec![frame("a"), frame("a"), frame("b")];
+        let (frames, delays) = dedup_frames(frames, vec![40, 60, 100]);
+        assert_eq!(frames, vec![frame("a"), frame("b")]);
+        assert_eq!(delays, vec![100, 100]);
+    }
+
+    #[test]
+    fn dedup_keeps_non_consecutive_repeats_and_the_total_duration() {
+        let frames = vec![frame("a"), frame("b"), frame("a"), frame("a")];
+        let (frames, delays) = dedup_frames(frames, vec![u16::MAX, 10, u16::MAX, 5]);
+        assert_eq!(frames, vec![frame("a"), frame("b"), frame("a"), frame("a")]);
+        assert_eq!(delays, vec![u16::MAX, 10, u16::MAX, 5]);
+    }
+
+    #[test]
+    fn reverse_and_boomerang_keep_delays_aligned_with_frames() {
+        let frames = vec!["a", "b", "c", "d"];
+        let delays = vec![10u16, 20, 30, 40];
+
+        assert_eq!(apply_playback_order(frames.clone(), true, false), vec!["d", "c", "b", "a"]);
+        assert_eq!(apply_playback_order(delays.clone(), true, false), vec![40, 30, 20, 10]);
+
+        // The end frames are not repeated when the animation turns around.
+        assert_eq!(apply_playback_order(frames.clone(), false, true), vec!["a", "b", "c", "d", "c", "b"]);
+        assert_eq!(apply_playback_order(delays.clone(), false, true), vec![10, 20, 30, 40, 30, 20]);
+
+        assert_eq!(apply_playback_order(frames, true, true), vec!["d", "c", "b", "a", "b", "c"]);
+        assert_eq!(apply_playback_order(delays, true, true), vec![40, 30, 20, 10, 20, 30]);
+    }
+
+    #[test]
+    fn boomerang_leaves_two_frames_alone() {
+        assert_eq!(apply_playback_order(vec![1, 2], false, true), vec![1, 2]);
+    }
+
+    #[test]
+    fn unique_names_count_up_past_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.gif");
+        assert_eq!(unique_output_path(path.clone()), path);
+
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(unique_output_path(path.clone()), dir.path().join("out_1.gif"));
+
+        std::fs::write(dir.path().join("out_1.gif"), b"").unwrap();
+        std::fs::write(dir.path().join("out_3.gif"), b"").unwrap();
+        assert_eq!(unique_output_path(path), dir.path().join("out_2.gif"));
+    }
+
+    #[test]
+    fn unique_names_without_an_extension_append_the_counter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("frames");
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(unique_output_path(path), dir.path().join("frames_1"));
+    }
+
+    fn gif_of_shade(shade: u8) -> GifData {
+        // A few stray pixels of the opposite extreme, so the fixtures are only mostly dark or light.
+        let image = image::ImageBuffer::from_fn(16, 16, |x, y| {
+            let value = if (x + y) % 7 == 0 { 255 - shade } else { shade };
+            image::Rgba([value, value, value, 255])
+        });
+        let frames = vec![monochora::GifFrame { image, delay_time_ms: 100 }; 2];
+        GifData { frames, width: 16, height: 16, loop_count: 0 }
+    }
+
+    #[test]
+    fn auto_invert_inverts_mostly_dark_sources_only() {
+        let mut config = AsciiConverterConfig::default();
+        let brightness = apply_auto_invert(&gif_of_shade(10), &mut config).unwrap();
+        assert!(brightness < AUTO_INVERT_THRESHOLD);
+        assert!(config.invert);
+
+        let brightness = apply_auto_invert(&gif_of_shade(245), &mut config).unwrap();
+        assert!(brightness >= AUTO_INVERT_THRESHOLD);
+        assert!(!config.invert);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn named_pipes_are_not_treated_as_existing_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("out.txt");
+        assert!(std::process::Command::new("mkfifo").arg(&fifo).status().unwrap().success());
+
+        assert!(is_named_pipe(&fifo));
+        assert!(!is_existing_output_file(&fifo));
+        assert_eq!(unique_output_path(fifo.clone()), fifo);
+    }
+
+    fn parse_with_config(config: &str, cli_args: &[&str]) -> Result<Args, MonochoraError> {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("monochora.toml");
+        std::fs::write(&config_path, config).unwrap();
+
+        let mut argv: Vec<OsString> = ["monochora", "-i", "input.gif", "--config"].iter().map(OsString::from).collect();
+        argv.push(config_path.into_os_string());
+        argv.extend(cli_args.iter().map(OsString::from));
+        parse_args_from(argv)
+    }
+
+    #[test]
+    fn command_line_values_override_config_values() {
+        let args = parse_with_config("width = 120\ncolored = true\n", &[]).unwrap();
+        assert_eq!(args.width, Some(120));
+        assert!(args.colored);
+
+        let args = parse_with_config("width = 120\n", &["-w", "80"]).unwrap();
+        assert_eq!(args.width, Some(80));
+    }
+
+    #[test]
+    fn unknown_config_keys_are_rejected() {
+        let err = parse_with_config("widht = 120\n", &[]).unwrap_err();
+        assert!(err.to_string().contains("Unknown config key: widht"));
+    }
+
+    #[test]
+    fn negated_flags_switch_off_config_booleans() {
+        let args = parse_with_config("colored = true\n", &["--no-colored"]).unwrap();
+        assert!(!args.colored);
+
+        // Without a config file the negation is simply the default.
+        let args = parse_args_from(["monochora", "-i", "input.gif", "--no-colored"].iter().map(OsString::from).collect()).unwrap();
+        assert!(!args.colored);
+    }
+}
@@ -1,33 +1,156 @@
+use crate::converter::{ansi_palette_color, TRANSPARENT_CELL};
+use crate::handler::{normalize_frame_delays, DEFAULT_FRAME_DELAY_MS};
 use crate::{MonochoraError, Result};
-use gif::{Encoder, Frame, Repeat};
+use gif::{DisposalMethod, Encoder, Extension, Frame, Repeat};
+use image::imageops::{self, FilterType};
 use image::{Rgb, RgbImage};
 use imageproc::drawing::draw_text_mut;
 use rusttype::{Font, Scale};
-use std::fs::File;
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::borrow::Cow;
 use rayon::prelude::*;
-use tracing::debug;
+use tracing::{debug, warn};
 use regex::Regex;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 use std::collections::HashMap;
+use serde::{Serialize, Serializer};
 
 const MAX_FONT_SIZE: f32 = 200.0;
 const MAX_LINE_HEIGHT_MULTIPLIER: f32 = 10.0;
 const DEFAULT_CHAR_WIDTH_RATIO: f32 = 0.6;
+const MIN_CHAR_WIDTH_RATIO: f32 = 0.1;
+const MAX_CHAR_WIDTH_RATIO: f32 = 2.0;
 const DEFAULT_PADDING: u32 = 20;
 const MAX_PALETTE_COLORS: usize = 256;
-const DEFAULT_FRAME_DELAY: u16 = 100;
+const MIN_PALETTE_COLORS: usize = 2;
 const MIN_FRAME_DELAY: u16 = 1;
+const MISSING_GLYPH_SUBSTITUTE: char = '?';
+const MAX_COMMENT_LENGTH: usize = 2048;
+const DEFAULT_GRAY_LEVELS: usize = 128;
+const MAX_SIZE_FIT_ATTEMPTS: usize = 12;
+const MIN_SIZE_FIT_PALETTE: usize = 16;
+const MIN_SIZE_FIT_SCALE: f32 = 0.25;
+const GLOBAL_PALETTE_SAMPLE_FRAMES: usize = 32;
+const GLOBAL_PALETTE_PIXEL_STRIDE: usize = 3;
+const MAX_SUPERSAMPLE: u32 = 4;
+const MAX_SUPERSAMPLED_PIXELS: u64 = 4096 * 4096;
 
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuantizeMethod {
+    #[default]
+    Nearest,
+    NearestWithDither,
+    MedianCut,
+}
+
+impl QuantizeMethod {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "nearest" => Ok(Self::Nearest),
+            "dither" | "nearest-with-dither" => Ok(Self::NearestWithDither),
+            "median-cut" | "mediancut" => Ok(Self::MedianCut),
+            _ => Err(MonochoraError::Config(format!(
+                "Invalid quantization method '{}', expected nearest, dither or median-cut", name
+            ))),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptionPosition {
+    Top,
+    #[default]
+    Bottom,
+}
+
+impl CaptionPosition {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "top" => Ok(Self::Top),
+            "bottom" => Ok(Self::Bottom),
+            _ => Err(MonochoraError::Config(format!(
+                "Invalid caption position '{}', expected top or bottom", name
+            ))),
+        }
+    }
+}
+
+// Where each line's characters go: left to right (the default), right to left, or top to bottom with
+// lines as columns ordered right to left, as in traditional CJK text.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextLayout {
+    #[default]
+    Ltr,
+    Rtl,
+    Vertical,
+}
+
+impl TextLayout {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "ltr" => Ok(Self::Ltr),
+            "rtl" => Ok(Self::Rtl),
+            "vertical" | "ttb" => Ok(Self::Vertical),
+            _ => Err(MonochoraError::Config(format!(
+                "Invalid text layout '{}', expected ltr, rtl or vertical", name
+            ))),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize)]
+pub struct Caption {
+    pub text: String,
+    pub position: CaptionPosition,
+    #[serde(serialize_with = "serialize_rgb")]
+    pub color: Rgb<u8>,
+}
+
+impl Caption {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            position: CaptionPosition::default(),
+            color: Rgb([255, 255, 255]),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Serialize)]
 pub struct AsciiGifOutputOptions {
     pub font_size: f32,
+    #[serde(serialize_with = "serialize_rgb")]
     pub bg_color: Rgb<u8>,
+    #[serde(serialize_with = "serialize_rgb")]
     pub text_color: Rgb<u8>,
     pub line_height_multiplier: f32,
     pub preserve_input_dimensions: bool,
     pub colored: bool,
+    pub substitute_missing: bool,
+    pub fallback_fonts: Vec<PathBuf>,
+    pub max_colors: usize,
+    pub optimize_frames: bool,
+    pub comment: Option<String>,
+    pub transparent: bool,
+    pub quantize: QuantizeMethod,
+    pub gray_levels: usize,
+    pub caption: Option<Caption>,
+    pub layout: TextLayout,
+    pub char_width_ratio: Option<f32>,
+    pub global_palette: bool,
+    pub supersample: u32,
+}
+
+fn serialize_rgb<S: Serializer>(color: &Rgb<u8>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    color.0.serialize(serializer)
 }
 
 impl Default for AsciiGifOutputOptions {
@@ -39,6 +162,19 @@ impl Default for AsciiGifOutputOptions {
             line_height_multiplier: 1.0,
             preserve_input_dimensions: true,
             colored: false,
+            substitute_missing: false,
+            fallback_fonts: Vec::new(),
+            max_colors: MAX_PALETTE_COLORS,
+            optimize_frames: false,
+            comment: None,
+            transparent: false,
+            quantize: QuantizeMethod::Nearest,
+            gray_levels: DEFAULT_GRAY_LEVELS,
+            caption: None,
+            layout: TextLayout::Ltr,
+            char_width_ratio: None,
+            global_palette: false,
+            supersample: 1,
         }
     }
 }
@@ -54,6 +190,61 @@ impl AsciiGifOutputOptions {
                 format!("Invalid line height multiplier: {}", self.line_height_multiplier)
             ));
         }
+
+        if let Some(ratio) = self.char_width_ratio
+            && !(MIN_CHAR_WIDTH_RATIO..=MAX_CHAR_WIDTH_RATIO).contains(&ratio) {
+            return Err(MonochoraError::Config(format!(
+                "Invalid character width ratio: {} (must be between {} and {})", ratio, MIN_CHAR_WIDTH_RATIO, MAX_CHAR_WIDTH_RATIO
+            )));
+        }
+
+        if self.supersample == 0 || self.supersample > MAX_SUPERSAMPLE {
+            return Err(MonochoraError::Config(format!(
+                "Invalid supersampling factor: {} (must be between 1 and {})", self.supersample, MAX_SUPERSAMPLE
+            )));
+        }
+
+        if self.max_colors < MIN_PALETTE_COLORS || self.max_colors > MAX_PALETTE_COLORS {
+            return Err(MonochoraError::Config(
+                format!("Invalid palette size: {} (must be between {} and {})", self.max_colors, MIN_PALETTE_COLORS, MAX_PALETTE_COLORS)
+            ));
+        }
+
+        // Background and text color take two palette entries; the shades between them get the rest.
+        if self.gray_levels == 0 || self.gray_levels > MAX_PALETTE_COLORS - 2 {
+            return Err(MonochoraError::Config(
+                format!("Invalid gray level count: {} (must be between 1 and {})", self.gray_levels, MAX_PALETTE_COLORS - 2)
+            ));
+        }
+
+        if let Some(caption) = &self.caption
+            && (caption.text.is_empty() || caption.text.chars().any(|ch| ch.is_control())) {
+            return Err(MonochoraError::Config(
+                "Caption must be a single non-empty line without control characters".to_string()
+            ));
+        }
+
+        // Kept regions would show the previous frame through transparent cells.
+        if self.transparent && self.optimize_frames {
+            return Err(MonochoraError::Config(
+                "Frame optimization cannot be combined with transparent output".to_string()
+            ));
+        }
+
+        if let Some(comment) = &self.comment {
+            if comment.len() > MAX_COMMENT_LENGTH {
+                return Err(MonochoraError::Config(
+                    format!("GIF comment is too long: {} characters (maximum {})", comment.len(), MAX_COMMENT_LENGTH)
+                ));
+            }
+
+            // Comment blocks are meant to hold 7-bit ASCII text, so anything else is refused rather than mangled.
+            if let Some(ch) = comment.chars().find(|ch| !(ch.is_ascii_graphic() || *ch == ' ' || *ch == '\n')) {
+                return Err(MonochoraError::Config(
+                    format!("GIF comment may only contain printable ASCII characters (found {:?})", ch)
+                ));
+            }
+        }
         
         Ok(())
     }
@@ -79,15 +270,148 @@ static ANSI_REGEX: OnceLock<Regex> = OnceLock::new();
 
 fn get_ansi_regex() -> &'static Regex {
     ANSI_REGEX.get_or_init(|| {
-        Regex::new(r"\x1b\[38;2;(\d+);(\d+);(\d+)m([^\x1b]*)")
+        Regex::new(r"\x1b\[([0-9;]*)m([^\x1b]*)")
             .expect("Failed to compile ANSI regex")
     })
 }
 
+// Resolves an SGR foreground escape (truecolor, 256-color or the 16 basic colors) to the color it selects.
+fn sgr_foreground(params: &str, current: Rgb<u8>, default_color: Rgb<u8>) -> Rgb<u8> {
+    let codes: Vec<u16> = params
+        .split(';')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let channel = |value: u16| u8::try_from(value).ok();
+
+    match codes.as_slice() {
+        [38, 2, r, g, b, ..] => match (channel(*r), channel(*g), channel(*b)) {
+            (Some(r), Some(g), Some(b)) => Rgb([r, g, b]),
+            _ => current,
+        },
+        [38, 5, index, ..] => channel(*index)
+            .map(|index| {
+                let (r, g, b) = ansi_palette_color(index);
+                Rgb([r, g, b])
+            })
+            .unwrap_or(current),
+        [code @ 30..=37] | [code @ 90..=97] => {
+            let index = if *code >= 90 { code - 90 + 8 } else { code - 30 };
+            let (r, g, b) = ansi_palette_color(index as u8);
+            Rgb([r, g, b])
+        }
+        [0] | [39] => default_color,
+        _ => current,
+    }
+}
+
+struct FontChain {
+    fonts: Vec<Font<'static>>,
+    cell_width_ratio: Option<f32>,
+}
+
+impl FontChain {
+    fn load(fallback_fonts: &[PathBuf], cell_width_ratio: Option<f32>) -> Result<Self> {
+        let font_data = include_bytes!("../resources/DejaVuSansMono.ttf");
+        let embedded = Font::try_from_bytes(font_data as &[u8])
+            .ok_or_else(|| MonochoraError::FontLoad("Failed to load embedded font".to_string()))?;
+
+        let mut fonts = vec![embedded];
+
+        for path in fallback_fonts {
+            let data = std::fs::read(path)
+                .map_err(|e| MonochoraError::FontLoad(format!("Failed to read font file {}: {}", path.display(), e)))?;
+            let font = Font::try_from_vec(data)
+                .ok_or_else(|| MonochoraError::FontLoad(format!("Failed to parse font file: {}", path.display())))?;
+            debug!("Loaded fallback font: {}", path.display());
+            fonts.push(font);
+        }
+
+        Ok(Self { fonts, cell_width_ratio })
+    }
+
+    fn font_index_for(&self, ch: char) -> Option<usize> {
+        self.fonts.iter().position(|font| font.glyph(ch).id().0 != 0)
+    }
+
+    fn supports(&self, ch: char) -> bool {
+        self.font_index_for(ch).is_some()
+    }
+
+    // Cells are laid out on the primary font's monospace advance, whichever font draws the glyph,
+    // unless a cell width ratio (of the font size) is configured.
+    fn advance_width(&self, scale: Scale) -> f32 {
+        match self.cell_width_ratio {
+            Some(ratio) => scale.x * ratio,
+            None => self.fonts[0].glyph(' ').scaled(scale).h_metrics().advance_width,
+        }
+    }
+
+    // Returns the line advance and the offset that centres the glyph box within any extra leading.
+    fn line_metrics(&self, scale: Scale, multiplier: f32) -> (f32, f32) {
+        let v_metrics = self.fonts[0].v_metrics(scale);
+        let glyph_height = v_metrics.ascent - v_metrics.descent;
+        let natural = glyph_height + v_metrics.line_gap;
+        let line_height = if natural > 0.0 { natural * multiplier } else { scale.y * multiplier };
+        let offset = ((line_height - glyph_height) / 2.0).max(0.0);
+        (line_height, offset)
+    }
+}
+
+fn draw_text_with_fallback(
+    image: &mut RgbImage,
+    color: Rgb<u8>,
+    y_position: u32,
+    scale: Scale,
+    fonts: &FontChain,
+    text: &str,
+) {
+    draw_text_with_fallback_at(image, color, 0.0, y_position, scale, fonts, text);
+}
+
+fn draw_text_with_fallback_at(
+    image: &mut RgbImage,
+    color: Rgb<u8>,
+    x_position: f32,
+    y_position: u32,
+    scale: Scale,
+    fonts: &FontChain,
+    text: &str,
+) {
+    let advance = fonts.advance_width(scale);
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let font_idx = fonts.font_index_for(chars[i]).unwrap_or(0);
+        let start_col = i;
+        let mut run = String::new();
+
+        // Runs are drawn with the font's own advance, so a configured cell width places every glyph on its own.
+        while i < chars.len() && fonts.font_index_for(chars[i]).unwrap_or(0) == font_idx {
+            run.push(chars[i]);
+            i += 1;
+            if fonts.cell_width_ratio.is_some() {
+                break;
+            }
+        }
+
+        draw_text_mut(
+            image,
+            color,
+            (x_position + start_col as f32 * advance) as i32,
+            y_position as i32,
+            scale,
+            &fonts.fonts[font_idx],
+            &run,
+        );
+    }
+}
+
 fn validate_font_charset_support(
     ascii_frames: &[Vec<String>],
-    font: &Font,
-) -> Result<()> {
+    fonts: &FontChain,
+    substitute_missing: bool,
+) -> Result<Vec<char>> {
     let mut unique_chars = std::collections::HashSet::new();
     
     for frame in ascii_frames {
@@ -107,7 +431,7 @@ fn validate_font_charset_support(
                     }
                     
                     if let Some(captures) = regex.captures(&line[mat.start()..mat.end()]) {
-                        for ch in captures[4].chars() {
+                        for ch in captures[2].chars() {
                             if !ch.is_control() {
                                 unique_chars.insert(ch);
                             }
@@ -138,8 +462,7 @@ fn validate_font_charset_support(
     let mut unsupported_chars = Vec::new();
     
     for &ch in &unique_chars {
-        let glyph = font.glyph(ch);
-        if glyph.id().0 == 0 {
+        if !fonts.supports(ch) {
             unsupported_chars.push(ch);
         }
     }
@@ -147,11 +470,38 @@ fn validate_font_charset_support(
     if !unsupported_chars.is_empty() {
         unsupported_chars.sort();
         let unsupported_str: String = unsupported_chars.iter().collect();
-        return Err(MonochoraError::UnsupportedFontCharacters {
-            characters: unsupported_str
-        });    }
+
+        if !substitute_missing {
+            return Err(MonochoraError::UnsupportedFontCharacters {
+                characters: unsupported_str
+            });
+        }
+
+        warn!(
+            "Font does not support the following characters, substituting '{}': {}",
+            MISSING_GLYPH_SUBSTITUTE, unsupported_str
+        );
+    }
     
-    Ok(())
+    Ok(unsupported_chars)
+}
+
+fn substitute_unsupported_characters(
+    ascii_frames: &[Vec<String>],
+    unsupported_chars: &[char],
+) -> Vec<Vec<String>> {
+    ascii_frames
+        .par_iter()
+        .map(|frame| {
+            frame.iter()
+                .map(|line| {
+                    line.chars()
+                        .map(|ch| if unsupported_chars.contains(&ch) { MISSING_GLYPH_SUBSTITUTE } else { ch })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
 }
 
 fn parse_line_to_colored_characters(line: &str, default_color: Rgb<u8>) -> Vec<ColoredCharacter> {
@@ -181,18 +531,12 @@ fn parse_line_to_colored_characters(line: &str, default_color: Rgb<u8>) -> Vec<C
         }
         
         if let Some(captures) = regex.captures(&line[mat.start()..mat.end()]) {
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                captures[1].parse::<u8>(),
-                captures[2].parse::<u8>(),
-                captures[3].parse::<u8>(),
-            ) {
-                current_color = Rgb([r, g, b]);
-                for ch in captures[4].chars() {
-                    result.push(ColoredCharacter { 
-                        character: ch, 
-                        color: current_color 
-                    });
-                }
+            current_color = sgr_foreground(&captures[1], current_color, default_color);
+            for ch in captures[2].chars() {
+                result.push(ColoredCharacter { 
+                    character: ch, 
+                    color: current_color 
+                });
             }
         }
         
@@ -219,7 +563,7 @@ fn render_colored_line_to_image(
     line: &str,
     y_position: u32,
     scale: Scale,
-    font: &Font,
+    fonts: &FontChain,
     options: &AsciiGifOutputOptions,
 ) -> Result<()> {
     let colored_chars = parse_line_to_colored_characters(line, options.text_color);
@@ -250,15 +594,7 @@ fn render_colored_line_to_image(
         
         let positioned_text: String = positioned_line.into_iter().collect();
         
-        draw_text_mut(
-            image,
-            current_color,
-            0,
-            y_position as i32,
-            scale,
-            font,
-            &positioned_text,
-        );
+        draw_text_with_fallback(image, current_color, y_position, scale, fonts, &positioned_text);
     }
     
     Ok(())
@@ -269,7 +605,7 @@ fn render_ascii_to_image_colored(
     width: u32,
     height: u32,
     scale: Scale,
-    font: &Font,
+    fonts: &FontChain,
     options: &AsciiGifOutputOptions,
 ) -> Result<RgbImage> {
     if width == 0 || height == 0 {
@@ -277,35 +613,29 @@ fn render_ascii_to_image_colored(
     }
     
     let mut image = RgbImage::from_pixel(width, height, options.bg_color);
-    let line_height = scale.y;
+    let (line_height, offset) = fonts.line_metrics(scale, options.line_height_multiplier);
 
     for (line_idx, line) in ascii_frame.iter().enumerate() {
-        let y = (line_idx as f32 * line_height) as u32;
+        let y = (line_idx as f32 * line_height + offset) as u32;
         
-        if y >= height.saturating_sub(scale.y as u32) {
+        // Glyphs running past the bottom edge are clipped by the rasterizer, so only stop once a line starts off-image.
+        if y >= height {
             break;
         }
         
         if line.contains('\x1b') {
-            render_colored_line_to_image(&mut image, line, y, scale, font, options)?;
+            render_colored_line_to_image(&mut image, line, y, scale, fonts, options)?;
         } else {
-            draw_text_mut(
-                &mut image,
-                options.text_color,
-                0,
-                y as i32,
-                scale,
-                font,
-                line,
-            );
+            draw_text_with_fallback(&mut image, options.text_color, y, scale, fonts, line);
         }
     }
     
     Ok(image)
 }
 
-fn create_enhanced_color_palette(bg_color: Rgb<u8>) -> Vec<u8> {
-    let mut palette = Vec::with_capacity(MAX_PALETTE_COLORS * 3);
+fn create_enhanced_color_palette(bg_color: Rgb<u8>, max_colors: usize) -> Vec<u8> {
+    let max_colors = max_colors.clamp(MIN_PALETTE_COLORS, MAX_PALETTE_COLORS);
+    let mut palette = Vec::with_capacity(max_colors * 3);
     
     palette.extend_from_slice(&[bg_color[0], bg_color[1], bg_color[2]]);
     
@@ -335,22 +665,132 @@ fn create_enhanced_color_palette(bg_color: Rgb<u8>) -> Vec<u8> {
         palette.extend_from_slice(&[gray_value, gray_value, gray_value]);
     }
     
-    while palette.len() < MAX_PALETTE_COLORS * 3 {
+    while palette.len() < max_colors * 3 {
         palette.extend_from_slice(&[bg_color[0], bg_color[1], bg_color[2]]);
     }
     
-    palette.truncate(MAX_PALETTE_COLORS * 3);
+    palette.truncate(max_colors * 3);
+    palette
+}
+
+// Counts the non-background pixels on every `stride`-th diagonal, so subsampling never lines up with glyph columns.
+fn color_histogram(images: &[RgbImage], bg_color: Rgb<u8>, stride: usize) -> HashMap<[u8; 3], u32> {
+    images
+        .par_iter()
+        .map(|image| {
+            let mut counts: HashMap<[u8; 3], u32> = HashMap::new();
+            let sampled = image.enumerate_pixels().filter(|&(x, y, _)| ((x + y) as usize).is_multiple_of(stride));
+            for (_, _, pixel) in sampled.filter(|(_, _, pixel)| pixel.0 != bg_color.0) {
+                *counts.entry(pixel.0).or_insert(0) += 1;
+            }
+            counts
+        })
+        .reduce(HashMap::new, |mut merged, counts| {
+            for (color, count) in counts {
+                *merged.entry(color).or_insert(0) += count;
+            }
+            merged
+        })
+}
+
+// Median cut over every rendered pixel; the background keeps index 0 so empty cells stay exact.
+fn create_median_cut_palette(images: &[RgbImage], bg_color: Rgb<u8>, max_colors: usize) -> Vec<u8> {
+    median_cut_palette(color_histogram(images, bg_color, 1), bg_color, max_colors)
+}
+
+fn median_cut_palette(histogram: HashMap<[u8; 3], u32>, bg_color: Rgb<u8>, max_colors: usize) -> Vec<u8> {
+    let max_colors = max_colors.clamp(MIN_PALETTE_COLORS, MAX_PALETTE_COLORS);
+
+    let mut boxes: Vec<Vec<([u8; 3], u32)>> = Vec::new();
+    if !histogram.is_empty() {
+        boxes.push(histogram.into_iter().collect());
+    }
+
+    while boxes.len() < max_colors - 1 {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(index, colors)| (index, widest_channel(colors)))
+            .max_by_key(|&(_, (_, range))| range);
+        let Some((index, (channel, _))) = widest else {
+            break;
+        };
+
+        let mut colors = boxes.swap_remove(index);
+        // Ties are broken on the whole color: the histogram's HashMap order changes between runs.
+        colors.sort_unstable_by_key(|&(color, _)| (color[channel], color));
+        let total: u64 = colors.iter().map(|&(_, count)| count as u64).sum();
+        let mut cumulative = 0u64;
+        let median = colors
+            .iter()
+            .position(|&(_, count)| {
+                cumulative += count as u64;
+                cumulative * 2 >= total
+            })
+            .unwrap_or(0);
+        let upper = colors.split_off((median + 1).clamp(1, colors.len() - 1));
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    let mut palette = Vec::with_capacity(max_colors * 3);
+    palette.extend_from_slice(&bg_color.0);
+    for colors in &boxes {
+        let total: u64 = colors.iter().map(|&(_, count)| count as u64).sum();
+        for channel in 0..3 {
+            let weighted: u64 = colors.iter().map(|&(color, count)| color[channel] as u64 * count as u64).sum();
+            palette.push((weighted / total.max(1)) as u8);
+        }
+    }
+
+    while palette.len() < max_colors * 3 {
+        palette.extend_from_slice(&bg_color.0);
+    }
+
     palette
 }
 
-fn create_optimized_palette(bg_color: Rgb<u8>, text_color: Rgb<u8>) -> Vec<u8> {
+// One palette for the whole animation without keeping every frame: up to GLOBAL_PALETTE_SAMPLE_FRAMES
+// evenly spaced frames are rendered and every GLOBAL_PALETTE_PIXEL_STRIDE-th pixel is counted.
+fn create_global_palette<F>(ascii_frames: &[Vec<String>], render_frame: &F, bg_color: Rgb<u8>, max_colors: usize) -> Result<Vec<u8>>
+where
+    F: Fn(&[String]) -> Result<RgbImage> + Sync,
+{
+    let step = ascii_frames.len().div_ceil(GLOBAL_PALETTE_SAMPLE_FRAMES).max(1);
+    let samples = ascii_frames
+        .par_iter()
+        .step_by(step)
+        .map(|ascii_frame| render_frame(ascii_frame))
+        .collect::<Result<Vec<_>>>()?;
+
+    debug!("Building a global palette from {} of {} frames", samples.len(), ascii_frames.len());
+    Ok(median_cut_palette(color_histogram(&samples, bg_color, GLOBAL_PALETTE_PIXEL_STRIDE), bg_color, max_colors))
+}
+
+// Returns the channel with the largest spread and that spread.
+fn widest_channel(colors: &[([u8; 3], u32)]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = colors.iter().fold((u8::MAX, u8::MIN), |(min, max), (color, _)| {
+                (min.min(color[channel]), max.max(color[channel]))
+            });
+            (channel, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+// Anti-aliased glyph edges blend text into background, so the ramp between the two covers every edge pixel.
+fn create_optimized_palette(bg_color: Rgb<u8>, text_color: Rgb<u8>, gray_levels: usize) -> Vec<u8> {
     let mut palette = Vec::with_capacity(MAX_PALETTE_COLORS * 3);
     
     palette.extend_from_slice(&[bg_color[0], bg_color[1], bg_color[2]]);
     palette.extend_from_slice(&[text_color[0], text_color[1], text_color[2]]);
     
-    for i in 1..32 {
-        let ratio = i as f32 / 32.0;
+    let steps = gray_levels.min(MAX_PALETTE_COLORS - 2) + 1;
+    for i in 1..steps {
+        let ratio = i as f32 / steps as f32;
         let r = (bg_color[0] as f32 * (1.0 - ratio) + text_color[0] as f32 * ratio) as u8;
         let g = (bg_color[1] as f32 * (1.0 - ratio) + text_color[1] as f32 * ratio) as u8;
         let b = (bg_color[2] as f32 * (1.0 - ratio) + text_color[2] as f32 * ratio) as u8;
@@ -386,56 +826,97 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
     (r, g, b)
 }
 
-type ColorCache = HashMap<[u8; 3], u8>;
+// Bits kept per channel when indexing the colored-output lookup table (32x32x32 buckets).
+const COLOR_LUT_BITS: u32 = 5;
+const COLOR_LUT_SHIFT: u32 = 8 - COLOR_LUT_BITS;
+
+struct ColorCache {
+    exact: HashMap<[u8; 3], u8>,
+    lut: Option<Vec<u8>>,
+}
 
 fn create_color_cache(palette: &[u8]) -> ColorCache {
-    let mut cache = HashMap::with_capacity(MAX_PALETTE_COLORS);
+    let mut exact = HashMap::with_capacity(MAX_PALETTE_COLORS);
     let colors_count = palette.len() / 3;
     
     for i in 0..colors_count {
         let idx = i * 3;
         if idx + 2 < palette.len() {
             let key = [palette[idx], palette[idx + 1], palette[idx + 2]];
-            cache.insert(key, i as u8);
+            exact.insert(key, i as u8);
         }
     }
     
+    ColorCache { exact, lut: None }
+}
+
+// Colored frames are full of blended glyph edges that match no palette entry, and the palette is the
+// same for every frame, so the nearest color of each coarse RGB bucket is precomputed once and shared.
+fn create_color_cache_with_lut(palette: &[u8]) -> ColorCache {
+    let mut cache = create_color_cache(palette);
+    let buckets = 1usize << COLOR_LUT_BITS;
+    let half_bucket = 1u8 << (COLOR_LUT_SHIFT - 1);
+
+    cache.lut = Some(
+        (0..buckets * buckets * buckets)
+            .into_par_iter()
+            .map(|bucket| {
+                let center = [
+                    ((bucket / (buckets * buckets)) << COLOR_LUT_SHIFT) as u8 + half_bucket,
+                    (((bucket / buckets) % buckets) << COLOR_LUT_SHIFT) as u8 + half_bucket,
+                    ((bucket % buckets) << COLOR_LUT_SHIFT) as u8 + half_bucket,
+                ];
+                nearest_palette_index(center, palette)
+            })
+            .collect(),
+    );
     cache
 }
 
-fn find_closest_color(rgb: [u8; 3], palette: &[u8], cache: &ColorCache) -> u8 {
-    if let Some(&cached_index) = cache.get(&rgb) {
-        return cached_index;
-    }
-    
+fn color_lut_bucket(rgb: [u8; 3]) -> usize {
+    let bits = COLOR_LUT_BITS as usize;
+    ((rgb[0] >> COLOR_LUT_SHIFT) as usize) << (2 * bits)
+        | ((rgb[1] >> COLOR_LUT_SHIFT) as usize) << bits
+        | (rgb[2] >> COLOR_LUT_SHIFT) as usize
+}
+
+fn palette_distance(rgb: [u8; 3], palette: &[u8], index: usize) -> u32 {
+    let palette_idx = index * 3;
+    let dr = rgb[0] as i32 - palette[palette_idx] as i32;
+    let dg = rgb[1] as i32 - palette[palette_idx + 1] as i32;
+    let db = rgb[2] as i32 - palette[palette_idx + 2] as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+fn nearest_palette_index(rgb: [u8; 3], palette: &[u8]) -> u8 {
     let colors_count = palette.len() / 3;
     let mut min_distance = u32::MAX;
     let mut best_index = 0u8;
     
     for i in 0..colors_count {
-        let palette_idx = i * 3;
-        if palette_idx + 2 < palette.len() {
-            let pr = palette[palette_idx];
-            let pg = palette[palette_idx + 1];
-            let pb = palette[palette_idx + 2];
-            
-            let dr = rgb[0] as i32 - pr as i32;
-            let dg = rgb[1] as i32 - pg as i32;
-            let db = rgb[2] as i32 - pb as i32;
-            
-            let distance = (dr * dr + dg * dg + db * db) as u32;
-            
-            if distance < min_distance {
-                min_distance = distance;
-                best_index = i as u8;
-                if distance == 0 { break; }
-            }
+        let distance = palette_distance(rgb, palette, i);
+        if distance < min_distance {
+            min_distance = distance;
+            best_index = i as u8;
+            if distance == 0 { break; }
         }
     }
     
     best_index
 }
 
+fn find_closest_color(rgb: [u8; 3], palette: &[u8], cache: &ColorCache) -> u8 {
+    if let Some(&cached_index) = cache.exact.get(&rgb) {
+        return cached_index;
+    }
+
+    if let Some(lut) = &cache.lut {
+        return lut[color_lut_bucket(rgb)];
+    }
+    
+    nearest_palette_index(rgb, palette)
+}
+
 fn quantize_image(image: &RgbImage, palette: &[u8], cache: &ColorCache) -> Result<Vec<u8>> {
     let colors_count = palette.len() / 3;
     if colors_count == 0 {
@@ -454,79 +935,264 @@ fn quantize_image(image: &RgbImage, palette: &[u8], cache: &ColorCache) -> Resul
     Ok(indexed_data)
 }
 
+// Floyd-Steinberg error diffusion; rows are processed in order, so each frame is dithered serially.
+fn quantize_image_dithered(image: &RgbImage, palette: &[u8], cache: &ColorCache) -> Result<Vec<u8>> {
+    if palette.len() < 3 {
+        return Err(MonochoraError::Config("Empty color palette".to_string()));
+    }
+
+    let width = image.width() as usize;
+    let mut indexed_data = Vec::with_capacity(width * image.height() as usize);
+    // Padded by one cell on each side so neighbors never need bounds checks.
+    let mut current_errors = vec![[0.0f32; 3]; width + 2];
+    let mut next_errors = vec![[0.0f32; 3]; width + 2];
+
+    for row in image.rows() {
+        for (x, pixel) in row.enumerate() {
+            let mut target = [0.0f32; 3];
+            let mut rgb = [0u8; 3];
+            for channel in 0..3 {
+                target[channel] = pixel[channel] as f32 + current_errors[x + 1][channel];
+                rgb[channel] = target[channel].round().clamp(0.0, 255.0) as u8;
+            }
+
+            let index = find_closest_color(rgb, palette, cache);
+            indexed_data.push(index);
+
+            let chosen = &palette[index as usize * 3..index as usize * 3 + 3];
+            for channel in 0..3 {
+                let error = target[channel] - chosen[channel] as f32;
+                current_errors[x + 2][channel] += error * 7.0 / 16.0;
+                next_errors[x][channel] += error * 3.0 / 16.0;
+                next_errors[x + 1][channel] += error * 5.0 / 16.0;
+                next_errors[x + 2][channel] += error / 16.0;
+            }
+        }
+
+        std::mem::swap(&mut current_errors, &mut next_errors);
+        next_errors.fill([0.0; 3]);
+    }
+
+    Ok(indexed_data)
+}
+
 fn render_ascii_to_image(
     ascii_frame: &[String],
     width: u32,
     height: u32,
     scale: Scale,
-    font: &Font,
+    fonts: &FontChain,
+    options: &AsciiGifOutputOptions,
+) -> Result<RgbImage> {
+    let mut image = render_ascii_frame_to_image(ascii_frame, width, height, scale, fonts, options)?;
+    if let Some(caption) = &options.caption {
+        draw_caption(&mut image, caption, scale, fonts, options);
+    }
+    Ok(image)
+}
+
+// Renders at supersample times the size and scales back down, so glyph edges come out anti-aliased.
+fn render_ascii_to_image_supersampled(
+    ascii_frame: &[String],
+    width: u32,
+    height: u32,
+    scale: Scale,
+    fonts: &FontChain,
+    options: &AsciiGifOutputOptions,
+) -> Result<RgbImage> {
+    let factor = options.supersample;
+    if factor <= 1 {
+        return render_ascii_to_image(ascii_frame, width, height, scale, fonts, options);
+    }
+
+    let large_scale = Scale { x: scale.x * factor as f32, y: scale.y * factor as f32 };
+    let large = render_ascii_to_image(ascii_frame, width * factor, height * factor, large_scale, fonts, options)?;
+    Ok(imageops::resize(&large, width, height, FilterType::Triangle))
+}
+
+// The caption sits on a background-colored band one text line tall, centered horizontally.
+fn draw_caption(image: &mut RgbImage, caption: &Caption, scale: Scale, fonts: &FontChain, options: &AsciiGifOutputOptions) {
+    let (width, height) = image.dimensions();
+    let (line_height, offset) = fonts.line_metrics(scale, options.line_height_multiplier);
+    let band_height = (line_height.ceil() as u32).min(height);
+    let band_top = match caption.position {
+        CaptionPosition::Top => 0,
+        CaptionPosition::Bottom => height - band_height,
+    };
+
+    for y in band_top..band_top + band_height {
+        for x in 0..width {
+            image.put_pixel(x, y, options.bg_color);
+        }
+    }
+
+    let text_width = caption.text.chars().count() as f32 * fonts.advance_width(scale);
+    let x_position = ((width as f32 - text_width) / 2.0).max(0.0);
+    draw_text_with_fallback_at(image, caption.color, x_position, band_top + offset as u32, scale, fonts, &caption.text);
+}
+
+fn render_ascii_frame_to_image(
+    ascii_frame: &[String],
+    width: u32,
+    height: u32,
+    scale: Scale,
+    fonts: &FontChain,
     options: &AsciiGifOutputOptions,
 ) -> Result<RgbImage> {
     if options.colored {
-        render_ascii_to_image_colored(ascii_frame, width, height, scale, font, options)
+        render_ascii_to_image_colored(ascii_frame, width, height, scale, fonts, options)
     } else {
         if width == 0 || height == 0 {
             return Err(MonochoraError::InvalidDimensions { width, height });
         }
         
         let mut image = RgbImage::from_pixel(width, height, options.bg_color);
-        let line_height = scale.y;
+        let (line_height, offset) = fonts.line_metrics(scale, options.line_height_multiplier);
 
         for (line_idx, line) in ascii_frame.iter().enumerate() {
-            let y = (line_idx as f32 * line_height) as u32;
+            let y = (line_idx as f32 * line_height + offset) as u32;
             
-            if y < height.saturating_sub(scale.y as u32) {
-                draw_text_mut(
-                    &mut image,
-                    options.text_color,
-                    0,
-                    y as i32, 
-                    scale,
-                    font,
-                    line,
-                );
+            if y >= height {
+                break;
             }
+
+            draw_text_with_fallback(&mut image, options.text_color, y, scale, fonts, line);
         }
         
         Ok(image)
     }
 }
 
-fn calculate_line_character_count(line: &str) -> usize {
-    if line.contains('\x1b') {
-        let regex = get_ansi_regex();
-        let mut count = 0;
-        let mut last_end = 0;
-        
-        for mat in regex.find_iter(line) {
-            if mat.start() > last_end {
-                count += line[last_end..mat.start()].chars().filter(|&c| c != '\x1b').count();
-            }
-            
-            if let Some(captures) = regex.captures(&line[mat.start()..mat.end()]) {
-                count += captures[4].chars().count();
+// Byte ranges of a line's pieces: an escape sequence (true) or one visible character (false). A CSI
+// sequence runs from "\x1b[" to its first final byte in 0x40..=0x7E.
+pub(crate) fn split_escapes(line: &str) -> Vec<(usize, usize, bool)> {
+    let bytes = line.as_bytes();
+    let mut pieces = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == 0x1b {
+            let mut end = i + 1;
+            if bytes.get(end) == Some(&b'[') {
+                end += 1;
+                while end < bytes.len() && !(0x40..=0x7e).contains(&bytes[end]) {
+                    end += 1;
+                }
             }
-            
-            last_end = mat.end();
-        }
-        
-        if last_end < line.len() {
-            let remaining = &line[last_end..];
-            count += remaining.replace("\x1b[0m", "").chars().filter(|&c| c != '\x1b').count();
+            end = (end + 1).min(bytes.len());
+            pieces.push((i, end, true));
+            i = end;
+        } else {
+            let len = line[i..].chars().next().map_or(1, char::len_utf8);
+            pieces.push((i, i + len, false));
+            i += len;
         }
-        
-        count
-    } else {
-        line.chars().count()
     }
+
+    pieces
 }
 
-fn calculate_dimensions_from_ascii(
-    ascii_frames: &[Vec<String>],
-    _options: &AsciiGifOutputOptions,
-) -> Result<RenderDimensions> {
-    if ascii_frames.is_empty() {
-        return Err(MonochoraError::Config("No ASCII frames provided".to_string()));
+pub(crate) fn calculate_line_character_count(line: &str) -> usize {
+    if !line.contains('\x1b') {
+        return line.chars().count();
+    }
+
+    // One escape can color a whole run of characters, so count everything outside escape sequences.
+    split_escapes(line)
+        .into_iter()
+        .filter(|&(start, end, is_escape)| !is_escape && !line[start..end].starts_with(char::is_control))
+        .count()
+}
+
+// One visible character and the escape sequences in effect for it (empty when uncolored).
+fn styled_cells(line: &str) -> Vec<(String, char)> {
+    let mut cells = Vec::new();
+    let mut style = String::new();
+
+    for (start, end, is_escape) in split_escapes(line) {
+        let piece = &line[start..end];
+        if is_escape {
+            if piece == "\x1b[0m" || piece == "\x1b[m" {
+                style.clear();
+            } else {
+                style.push_str(piece);
+            }
+        } else if let Some(ch) = piece.chars().next()
+            && !ch.is_control() {
+            cells.push((style.clone(), ch));
+        }
+    }
+
+    cells
+}
+
+fn join_styled_cells<'a>(cells: impl Iterator<Item = (&'a str, char)>) -> String {
+    let mut line = String::new();
+    let mut current = "";
+
+    for (style, ch) in cells {
+        if style != current {
+            if !current.is_empty() {
+                line.push_str("\x1b[0m");
+            }
+            line.push_str(style);
+            current = style;
+        }
+        line.push(ch);
+    }
+
+    if !current.is_empty() {
+        line.push_str("\x1b[0m");
+    }
+    line
+}
+
+fn cell_at<'a>(row: &'a [(String, char)], idx: usize, blank: &'a (String, char)) -> (&'a str, char) {
+    let (style, ch) = row.get(idx).unwrap_or(blank);
+    (style.as_str(), *ch)
+}
+
+// Rearranges every frame's cells for the layout. Lines are padded with spaces to the frame's widest
+// line first, so right-to-left lines stay right-aligned and vertical columns line up.
+pub fn layout_frame_lines(frames: &[Vec<String>], layout: TextLayout) -> Vec<Vec<String>> {
+    if layout == TextLayout::Ltr {
+        return frames.to_vec();
+    }
+
+    let blank = (String::new(), ' ');
+
+    frames
+        .par_iter()
+        .map(|frame| {
+            let rows: Vec<Vec<(String, char)>> = frame.iter().map(|line| styled_cells(line)).collect();
+            let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+            if layout == TextLayout::Vertical {
+                (0..width)
+                    .map(|idx| join_styled_cells(rows.iter().rev().map(|row| cell_at(row, idx, &blank))))
+                    .collect()
+            } else {
+                rows.iter()
+                    .map(|row| join_styled_cells((0..width).rev().map(|idx| cell_at(row, idx, &blank))))
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+fn apply_layout(frames: &[Vec<String>], layout: TextLayout) -> Cow<'_, [Vec<String>]> {
+    match layout {
+        TextLayout::Ltr => Cow::Borrowed(frames),
+        layout => Cow::Owned(layout_frame_lines(frames, layout)),
+    }
+}
+
+fn calculate_dimensions_from_ascii(
+    ascii_frames: &[Vec<String>],
+    _options: &AsciiGifOutputOptions,
+) -> Result<RenderDimensions> {
+    if ascii_frames.is_empty() {
+        return Err(MonochoraError::Config("No ASCII frames provided".to_string()));
     }
     
     let max_line_length = ascii_frames
@@ -553,8 +1219,27 @@ fn calculate_dimensions_from_ascii(
     })
 }
 
+fn cell_width(fonts: &FontChain, options: &AsciiGifOutputOptions, scale: Scale) -> f32 {
+    let measured = fonts.advance_width(scale);
+    if measured > 0.0 {
+        measured
+    } else {
+        options.font_size * DEFAULT_CHAR_WIDTH_RATIO
+    }
+}
+
+// Pixel size (width, height) of one rendered character cell, so callers can fit a character grid to a target image.
+pub fn rendered_cell_size(options: &AsciiGifOutputOptions) -> Result<(f32, f32)> {
+    options.validate()?;
+    let fonts = FontChain::load(&options.fallback_fonts, options.char_width_ratio)?;
+    let scale = Scale { x: options.font_size, y: options.font_size };
+    let (line_height, _) = fonts.line_metrics(scale, options.line_height_multiplier);
+    Ok((cell_width(&fonts, options, scale), line_height))
+}
+
 fn calculate_render_scale_and_dimensions(
     dimensions: &RenderDimensions,
+    fonts: &FontChain,
     options: &AsciiGifOutputOptions,
     target_dimensions: Option<(u32, u32)>,
 ) -> (u32, u32, Scale) {
@@ -572,16 +1257,377 @@ fn calculate_render_scale_and_dimensions(
                 y: options.font_size,
             };
 
-            let char_width = (options.font_size * DEFAULT_CHAR_WIDTH_RATIO) as u32;
-            let width = dimensions.max_line_length as u32 * char_width;
-            let line_height = (options.font_size * options.line_height_multiplier) as u32;
-            let height = dimensions.max_lines as u32 * line_height + DEFAULT_PADDING;
+            let width = (dimensions.max_line_length as f32 * cell_width(fonts, options, scale)).ceil() as u32;
+            let (line_height, _) = fonts.line_metrics(scale, options.line_height_multiplier);
+            let height = (dimensions.max_lines as f32 * line_height).ceil() as u32 + DEFAULT_PADDING;
             
             (width, height, scale)
         }
     }
 }
 
+struct RenderContext<'a> {
+    frames: Cow<'a, [Vec<String>]>,
+    fonts: FontChain,
+    width: u32,
+    height: u32,
+    scale: Scale,
+}
+
+fn prepare_render_context<'a>(
+    ascii_frames: &'a [Vec<String>],
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+) -> Result<RenderContext<'a>> {
+    let fonts = FontChain::load(&options.fallback_fonts, options.char_width_ratio)?;
+
+    // Caption glyphs are never substituted, so a missing one is always an error.
+    if let Some(caption) = &options.caption {
+        validate_font_charset_support(&[vec![caption.text.clone()]], &fonts, false)?;
+    }
+
+    let unsupported_chars = validate_font_charset_support(ascii_frames, &fonts, options.substitute_missing)?;
+    let frames = if unsupported_chars.is_empty() {
+        apply_layout(ascii_frames, options.layout)
+    } else {
+        Cow::Owned(apply_layout(&substitute_unsupported_characters(ascii_frames, &unsupported_chars), options.layout).into_owned())
+    };
+
+    let dimensions = calculate_dimensions_from_ascii(&frames, options)?;
+
+    if let Some((target_width, target_height)) = target_dimensions
+        && (target_width == 0 || target_height == 0) {
+        return Err(MonochoraError::InvalidDimensions { 
+            width: target_width, 
+            height: target_height 
+        });
+    }
+
+    let (width, height, scale) = calculate_render_scale_and_dimensions(&dimensions, &fonts, options, target_dimensions);
+
+    if width == 0 || height == 0 {
+        return Err(MonochoraError::InvalidDimensions { width, height });
+    }
+
+    Ok(RenderContext { frames, fonts, width, height, scale })
+}
+
+pub(crate) fn frame_file_name(frame_idx: usize, frame_count: usize, extension: &str) -> String {
+    let digits = frame_count.to_string().len().max(4);
+    format!("frame_{:0width$}.{}", frame_idx + 1, extension, width = digits)
+}
+
+pub fn ascii_frames_to_png_files<P: AsRef<Path>>(
+    ascii_frames: &[Vec<String>],
+    output_dir: P,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+) -> Result<Vec<PathBuf>> {
+    ascii_frames_to_image_files(ascii_frames, output_dir, options, target_dimensions, "png")
+}
+
+// The image format is chosen from the extension, e.g. "png" or "ppm".
+pub fn ascii_frames_to_image_files<P: AsRef<Path>>(
+    ascii_frames: &[Vec<String>],
+    output_dir: P,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+    extension: &str,
+) -> Result<Vec<PathBuf>> {
+    options.validate()?;
+
+    if ascii_frames.is_empty() {
+        return Err(MonochoraError::Config("No ASCII frames to render".to_string()));
+    }
+
+    let RenderContext { frames, fonts, width, height, scale } =
+        prepare_render_context(ascii_frames, options, target_dimensions)?;
+
+    let output_dir = output_dir.as_ref();
+    std::fs::create_dir_all(output_dir)
+        .map_err(MonochoraError::Io)?;
+
+    debug!("Rendering {} frames to {} files in {}", frames.len(), extension, output_dir.display());
+
+    frames
+        .par_iter()
+        .enumerate()
+        .map(|(frame_idx, ascii_frame)| -> Result<PathBuf> {
+            let image = render_ascii_to_image(ascii_frame, width, height, scale, &fonts, options)?;
+            let path = output_dir.join(frame_file_name(frame_idx, frames.len(), extension));
+            image.save(&path)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+pub fn ascii_frames_to_sprite_sheet<P: AsRef<Path>>(
+    ascii_frames: &[Vec<String>],
+    output_path: P,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+    columns: u32,
+) -> Result<()> {
+    options.validate()?;
+
+    if ascii_frames.is_empty() {
+        return Err(MonochoraError::Config("No ASCII frames to render".to_string()));
+    }
+
+    if columns == 0 {
+        return Err(MonochoraError::Config("Sprite sheet must have at least one column".to_string()));
+    }
+
+    let RenderContext { frames, fonts, width, height, scale } =
+        prepare_render_context(ascii_frames, options, target_dimensions)?;
+
+    let columns = columns.min(frames.len() as u32);
+    let rows = (frames.len() as u32).div_ceil(columns);
+    let (sheet_width, sheet_height) = match (width.checked_mul(columns), height.checked_mul(rows)) {
+        (Some(sheet_width), Some(sheet_height)) => (sheet_width, sheet_height),
+        _ => return Err(MonochoraError::InvalidDimensions { width, height }),
+    };
+
+    debug!("Rendering {} frames into a {}x{} sprite sheet ({} columns)", 
+        frames.len(), sheet_width, sheet_height, columns);
+
+    let tiles: Result<Vec<RgbImage>> = frames
+        .par_iter()
+        .map(|ascii_frame| render_ascii_to_image(ascii_frame, width, height, scale, &fonts, options))
+        .collect();
+
+    let mut sheet = RgbImage::from_pixel(sheet_width, sheet_height, options.bg_color);
+    for (frame_idx, tile) in tiles?.iter().enumerate() {
+        let column = frame_idx as u32 % columns;
+        let row = frame_idx as u32 / columns;
+        image::imageops::replace(&mut sheet, tile, (column * width) as i64, (row * height) as i64);
+    }
+
+    sheet.save(output_path.as_ref())?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FrameRegion {
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+}
+
+// Bounding box of the pixels that differ from the previous frame; an unchanged frame still
+// needs a 1x1 region so its delay is kept.
+fn changed_region(previous: &[u8], current: &[u8], width: u32, height: u32) -> FrameRegion {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, 0, 0);
+
+    for y in 0..height {
+        let row = (y * width) as usize..((y + 1) * width) as usize;
+        let (prev_row, cur_row) = (&previous[row.clone()], &current[row]);
+        if prev_row == cur_row {
+            continue;
+        }
+
+        let first = prev_row.iter().zip(cur_row).position(|(a, b)| a != b).unwrap_or(0) as u32;
+        let last = prev_row.iter().zip(cur_row).rposition(|(a, b)| a != b).unwrap_or(0) as u32;
+        min_x = min_x.min(first);
+        max_x = max_x.max(last);
+        min_y = min_y.min(y);
+        max_y = y;
+    }
+
+    if min_x > max_x || min_y > max_y {
+        return FrameRegion { left: 0, top: 0, width: 1, height: 1 };
+    }
+
+    FrameRegion { left: min_x, top: min_y, width: max_x - min_x + 1, height: max_y - min_y + 1 }
+}
+
+fn crop_indexed(pixels: &[u8], width: u32, region: &FrameRegion) -> Vec<u8> {
+    (region.top..region.top + region.height)
+        .flat_map(|y| {
+            let start = (y * width + region.left) as usize;
+            pixels[start..start + region.width as usize].iter().copied()
+        })
+        .collect()
+}
+
+// Visible columns of each line that hold the transparent-cell marker, skipping escape sequences.
+fn transparent_columns(frame: &[String]) -> Vec<Vec<usize>> {
+    frame
+        .iter()
+        .map(|line| {
+            let mut columns = Vec::new();
+            let mut column = 0;
+            for (start, end, is_escape) in split_escapes(line) {
+                let Some(ch) = line[start..end].chars().next() else { continue };
+                if !is_escape && !ch.is_control() {
+                    if ch == TRANSPARENT_CELL {
+                        columns.push(column);
+                    }
+                    column += 1;
+                }
+            }
+            columns
+        })
+        .collect()
+}
+
+// Background pixels inside transparent cells take the reserved index; glyph pixels spilling in from neighbors keep their color.
+fn mask_transparent_cells(
+    image: &RgbImage,
+    indexed_data: &mut [u8],
+    columns: &[Vec<usize>],
+    cell_size: (f32, f32),
+    bg_color: Rgb<u8>,
+    transparent_index: u8,
+) {
+    let (width, height) = image.dimensions();
+    let (advance, line_height) = cell_size;
+
+    for (line_idx, line_columns) in columns.iter().enumerate() {
+        let top = (line_idx as f32 * line_height) as u32;
+        let bottom = (((line_idx + 1) as f32 * line_height) as u32).min(height);
+
+        for &column in line_columns {
+            let left = (column as f32 * advance) as u32;
+            let right = (((column + 1) as f32 * advance) as u32).min(width);
+
+            for y in top..bottom {
+                for x in left..right {
+                    if *image.get_pixel(x, y) == bg_color {
+                        indexed_data[(y * width + x) as usize] = transparent_index;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn frame_delay_for(frame_delays: &[u16], frame_idx: usize) -> u16 {
+    frame_delays
+        .get(frame_idx)
+        .or_else(|| frame_delays.first())
+        .copied()
+        .unwrap_or(DEFAULT_FRAME_DELAY_MS)
+}
+
+#[cfg(feature = "webp")]
+pub fn ascii_frames_to_webp<P: AsRef<Path>>(
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    loop_count: u16,
+    output_path: P,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+) -> Result<()> {
+    options.validate()?;
+
+    if ascii_frames.is_empty() {
+        return Err(MonochoraError::Config("No ASCII frames to convert".to_string()));
+    }
+
+    if frame_delays.is_empty() {
+        return Err(MonochoraError::Config("No frame delays provided".to_string()));
+    }
+    let frame_delays = &*normalize_frame_delays(frame_delays);
+
+    let RenderContext { frames, fonts, width, height, scale } =
+        prepare_render_context(ascii_frames, options, target_dimensions)?;
+
+    debug!("Rendering {} frames in parallel for WebP (colored: {})", frames.len(), options.colored);
+
+    let images: Result<Vec<RgbImage>> = frames
+        .par_iter()
+        .map(|ascii_frame| render_ascii_to_image(ascii_frame, width, height, scale, &fonts, options))
+        .collect();
+    let images = images?;
+
+    // Rendered text has hard edges and few colors, which lossless mode keeps crisp and small.
+    let mut config = webp::WebPConfig::new()
+        .map_err(|_| MonochoraError::Animation("Failed to initialize WebP encoder config".to_string()))?;
+    config.lossless = 1;
+
+    let mut encoder = webp::AnimEncoder::new(width, height, &config);
+    encoder.set_loop_count(loop_count as i32);
+    encoder.set_bgcolor([options.bg_color[0], options.bg_color[1], options.bg_color[2], 255]);
+
+    let mut timestamp_ms: i32 = 0;
+    for (frame_idx, image) in images.iter().enumerate() {
+        encoder.add_frame(webp::AnimFrame::from_rgb(image.as_raw(), width, height, timestamp_ms));
+        timestamp_ms += frame_delay_for(frame_delays, frame_idx).max(MIN_FRAME_DELAY * 10) as i32;
+    }
+
+    let encoded = encoder.try_encode()
+        .map_err(|e| MonochoraError::Animation(format!("Failed to encode WebP animation: {:?}", e)))?;
+
+    write_output_file(output_path.as_ref(), &encoded)?;
+
+    debug!("Successfully wrote {} frames to WebP", images.len());
+    Ok(())
+}
+
+// Output files are written next to their target as "<name>.part" and renamed into place once complete,
+// so an interrupted conversion never leaves a truncated GIF behind. Paths of in-flight ".part" files are
+// tracked here so a Ctrl-C handler can delete them before exiting.
+static PARTIAL_OUTPUTS: OnceLock<Mutex<Vec<PathBuf>>> = OnceLock::new();
+
+fn partial_outputs() -> &'static Mutex<Vec<PathBuf>> {
+    PARTIAL_OUTPUTS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+struct PartialOutputGuard {
+    path: PathBuf,
+    committed: bool,
+}
+
+impl PartialOutputGuard {
+    fn new(path: PathBuf) -> Self {
+        if let Ok(mut pending) = partial_outputs().lock() {
+            pending.push(path.clone());
+        }
+        Self { path, committed: false }
+    }
+
+    fn commit(mut self, target: &Path) -> Result<()> {
+        std::fs::rename(&self.path, target).map_err(MonochoraError::Io)?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Drop for PartialOutputGuard {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.path);
+        }
+        if let Ok(mut pending) = partial_outputs().lock() {
+            pending.retain(|path| path != &self.path);
+        }
+    }
+}
+
+// Deletes every ".part" file still being written; returns how many were removed.
+pub fn remove_partial_outputs() -> usize {
+    let Ok(mut pending) = partial_outputs().lock() else {
+        return 0;
+    };
+    pending.drain(..).filter(|path| std::fs::remove_file(path).is_ok()).count()
+}
+
+fn write_output_file(output_path: &Path, bytes: &[u8]) -> Result<()> {
+    // Named pipes and other special files can't be renamed over, so they are written directly.
+    if std::fs::metadata(output_path).is_ok_and(|metadata| !metadata.is_file()) {
+        return std::fs::write(output_path, bytes).map_err(MonochoraError::Io);
+    }
+
+    let mut partial_name = output_path.file_name().unwrap_or_default().to_os_string();
+    partial_name.push(".part");
+    let guard = PartialOutputGuard::new(output_path.with_file_name(partial_name));
+
+    std::fs::write(&guard.path, bytes).map_err(MonochoraError::Io)?;
+    guard.commit(output_path)
+}
+
 pub fn ascii_frames_to_gif<P: AsRef<Path>>(
     ascii_frames: &[Vec<String>],
     frame_delays: &[u16],
@@ -607,6 +1653,42 @@ pub fn ascii_frames_to_gif_with_dimensions<P: AsRef<Path>>(
     options: &AsciiGifOutputOptions,
     target_dimensions: Option<(u32, u32)>,
 ) -> Result<()> {
+    ascii_frames_to_gif_with_progress(ascii_frames, frame_delays, loop_count, output_path, options, target_dimensions, None)
+}
+
+pub fn ascii_frames_to_gif_with_progress<P: AsRef<Path>>(
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    loop_count: u16,
+    output_path: P,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<()> {
+    let encoded = ascii_frames_to_gif_bytes_with_progress(ascii_frames, frame_delays, loop_count, options, target_dimensions, progress)?;
+    write_output_file(output_path.as_ref(), &encoded)
+}
+
+pub fn ascii_frames_to_gif_bytes(
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    loop_count: u16,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+) -> Result<Vec<u8>> {
+    ascii_frames_to_gif_bytes_with_progress(ascii_frames, frame_delays, loop_count, options, target_dimensions, None)
+}
+
+// progress is called with (frames written, total frames) after each frame reaches the encoder. Frames are
+// rendered in parallel but written in order, so the count only ever goes up and ends at the total.
+pub fn ascii_frames_to_gif_bytes_with_progress(
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    loop_count: u16,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<Vec<u8>> {
     options.validate()?;
     
     if ascii_frames.is_empty() {
@@ -616,44 +1698,84 @@ pub fn ascii_frames_to_gif_with_dimensions<P: AsRef<Path>>(
     if frame_delays.is_empty() {
         return Err(MonochoraError::Config("No frame delays provided".to_string()));
     }
+    let frame_delays = &*normalize_frame_delays(frame_delays);
+
+    // Marker cells are located before rendering, then drawn as ordinary spaces. The markers are found in
+    // laid-out frames so they line up with the cells prepare_render_context lays out.
+    let transparency: Option<Vec<Vec<Vec<usize>>>> = options.transparent
+        .then(|| apply_layout(ascii_frames, options.layout).par_iter().map(|frame| transparent_columns(frame)).collect());
+    let unmarked_frames: Cow<[Vec<String>]> = if options.transparent {
+        Cow::Owned(ascii_frames
+            .par_iter()
+            .map(|frame| frame.iter().map(|line| line.replace(TRANSPARENT_CELL, " ")).collect())
+            .collect())
+    } else {
+        Cow::Borrowed(ascii_frames)
+    };
     
-    let font_data = include_bytes!("../resources/DejaVuSansMono.ttf");
-    let font = Arc::new(
-        Font::try_from_bytes(font_data as &[u8])
-            .ok_or_else(|| MonochoraError::FontLoad("Failed to load embedded font".to_string()))?
-    );
+    let RenderContext { frames, fonts, width, height, scale } =
+        prepare_render_context(&unmarked_frames, options, target_dimensions)?;
+    let ascii_frames = frames.as_ref();
 
-    validate_font_charset_support(ascii_frames, &font)?;
+    // Each worker holds one supersampled frame at a time, so the bound is per frame.
+    let supersampled_pixels = (width as u64 * height as u64) * (options.supersample as u64).pow(2);
+    if options.supersample > 1 && supersampled_pixels > MAX_SUPERSAMPLED_PIXELS {
+        return Err(MonochoraError::Config(format!(
+            "Supersampling {}x{} frames by {} needs {} pixels per frame (maximum {}); lower the factor or the output size",
+            width, height, options.supersample, supersampled_pixels, MAX_SUPERSAMPLED_PIXELS
+        )));
+    }
 
-    let dimensions = calculate_dimensions_from_ascii(ascii_frames, options)?;
+    let render_frame = |ascii_frame: &[String]| render_ascii_to_image_supersampled(ascii_frame, width, height, scale, &fonts, options);
 
-    if let Some((target_width, target_height)) = target_dimensions {
-        if target_width == 0 || target_height == 0 {
-            return Err(MonochoraError::InvalidDimensions { 
-                width: target_width, 
-                height: target_height 
-            });
-        }
-    }
+    // Median cut needs every rendered frame before the palette exists, so those frames are kept for quantization.
+    let prerendered: Option<Vec<RgbImage>> = if options.quantize == QuantizeMethod::MedianCut {
+        Some(ascii_frames.par_iter().map(|ascii_frame| render_frame(ascii_frame)).collect::<Result<_>>()?)
+    } else {
+        None
+    };
 
-    let (width, height, scale) = calculate_render_scale_and_dimensions(&dimensions, options, target_dimensions);
+    let palette_colors = options.max_colors - usize::from(options.transparent);
+    let mut palette = match &prerendered {
+        Some(images) => {
+            let mut palette = create_median_cut_palette(images, options.bg_color, palette_colors);
+            palette.resize(options.max_colors * 3, 0);
+            palette
+        }
+        None if options.colored && options.global_palette => {
+            let mut palette = create_global_palette(ascii_frames, &render_frame, options.bg_color, palette_colors)?;
+            palette.resize(options.max_colors * 3, 0);
+            palette
+        }
+        None if options.colored => create_enhanced_color_palette(options.bg_color, options.max_colors),
+        None => create_optimized_palette(options.bg_color, options.text_color, options.gray_levels),
+    };
 
-    if width == 0 || height == 0 {
-        return Err(MonochoraError::InvalidDimensions { width, height });
+    // Monochrome palettes are a background-to-text ramp, so a caption color off that ramp takes the first spare slot.
+    if let Some(caption) = &options.caption
+        && prerendered.is_none() && !options.colored {
+        let slot = 2 + options.gray_levels;
+        if slot < MAX_PALETTE_COLORS - 1 {
+            palette[slot * 3..slot * 3 + 3].copy_from_slice(&caption.color.0);
+        }
     }
 
-    let file = File::create(output_path.as_ref())
-        .map_err(|e| MonochoraError::Io(e))?;
+    // The last palette entry is reserved for transparency and left out of quantization.
+    let transparent_index = options.transparent.then(|| {
+        let last = palette.len() - 3;
+        palette[last..].copy_from_slice(&options.bg_color.0);
+        (palette.len() / 3 - 1) as u8
+    });
+    let quantize_palette = if transparent_index.is_some() { &palette[..palette.len() - 3] } else { &palette[..] };
     
-    let palette = if options.colored {
-        create_enhanced_color_palette(options.bg_color)
+    let color_cache = if options.colored {
+        create_color_cache_with_lut(quantize_palette)
     } else {
-        create_optimized_palette(options.bg_color, options.text_color)
+        create_color_cache(quantize_palette)
     };
+    let cell_size = (fonts.advance_width(scale), fonts.line_metrics(scale, options.line_height_multiplier).0);
     
-    let color_cache = create_color_cache(&palette);
-    
-    let mut encoder = Encoder::new(file, width as u16, height as u16, &palette)
+    let mut encoder = Encoder::new(Vec::new(), width as u16, height as u16, &palette)
         .map_err(|e| MonochoraError::GifDecode(format!("Failed to create GIF encoder: {}", e)))?;
 
     let repeat_setting = if loop_count == 0 {
@@ -664,6 +1786,11 @@ pub fn ascii_frames_to_gif_with_dimensions<P: AsRef<Path>>(
     
     encoder.set_repeat(repeat_setting)
         .map_err(|e| MonochoraError::GifDecode(format!("Failed to set GIF repeat: {}", e)))?;
+
+    if let Some(comment) = &options.comment {
+        encoder.write_raw_extension(Extension::Comment.into(), &[comment.as_bytes()])
+            .map_err(|e| MonochoraError::GifDecode(format!("Failed to write GIF comment: {}", e)))?;
+    }
     
     debug!("Rendering {} frames in parallel (colored: {})", ascii_frames.len(), options.colored);
     
@@ -671,29 +1798,27 @@ pub fn ascii_frames_to_gif_with_dimensions<P: AsRef<Path>>(
         .par_iter()
         .enumerate()
         .map(|(frame_idx, ascii_frame)| -> Result<(Vec<u8>, u16)> {
-            let image = render_ascii_to_image(
-                ascii_frame, 
-                width, 
-                height, 
-                scale, 
-                &font, 
-                options
-            )?;
-
-            let frame_delay = if frame_idx < frame_delays.len() {
-                frame_delays[frame_idx]
-            } else if !frame_delays.is_empty() {
-                frame_delays[0]
-            } else {
-                DEFAULT_FRAME_DELAY
+            let image = match &prerendered {
+                Some(images) => Cow::Borrowed(&images[frame_idx]),
+                None => Cow::Owned(render_frame(ascii_frame)?),
             };
 
-            let indexed_data = quantize_image(&image, &palette, &color_cache)?;
+            let frame_delay = frame_delay_for(frame_delays, frame_idx);
+
+            let mut indexed_data = if options.quantize == QuantizeMethod::NearestWithDither {
+                quantize_image_dithered(&image, quantize_palette, &color_cache)?
+            } else {
+                quantize_image(&image, quantize_palette, &color_cache)?
+            };
+            if let (Some(transparency), Some(transparent_index)) = (&transparency, transparent_index) {
+                mask_transparent_cells(&image, &mut indexed_data, &transparency[frame_idx], cell_size, options.bg_color, transparent_index);
+            }
             Ok((indexed_data, frame_delay))
         })
         .collect();
     
     let rendered_frames = frame_results?;
+    let mut previous: Option<Vec<u8>> = None;
     
     for (frame_idx, (indexed_data, frame_delay)) in rendered_frames.into_iter().enumerate() {
         if indexed_data.len() != (width * height) as usize {
@@ -703,20 +1828,416 @@ pub fn ascii_frames_to_gif_with_dimensions<P: AsRef<Path>>(
             ));
         }
         
+        let region = match &previous {
+            Some(previous) if options.optimize_frames => changed_region(previous, &indexed_data, width, height),
+            _ => FrameRegion { left: 0, top: 0, width, height },
+        };
+        let pixels = crop_indexed(&indexed_data, width, &region);
+        
         let mut frame = Frame::from_palette_pixels(
-            width as u16,
-            height as u16,
-            &indexed_data,
+            region.width as u16,
+            region.height as u16,
+            &pixels,
             &palette,
-            None,
+            transparent_index,
         );
 
+        frame.left = region.left as u16;
+        frame.top = region.top as u16;
         frame.delay = (frame_delay / 10).max(MIN_FRAME_DELAY);
+        if options.optimize_frames {
+            frame.dispose = DisposalMethod::Keep;
+        } else if options.transparent {
+            frame.dispose = DisposalMethod::Background;
+        }
         
         encoder.write_frame(&frame)
             .map_err(|e| MonochoraError::GifDecode(format!("Failed to write frame {}: {}", frame_idx, e)))?;
+
+        if let Some(progress) = progress {
+            progress(frame_idx + 1, ascii_frames.len());
+        }
+
+        if options.optimize_frames {
+            previous = Some(indexed_data);
+        }
     }
 
-    debug!("Successfully wrote {} frames to GIF", ascii_frames.len());
-    Ok(())
+    debug!("Successfully encoded {} frames to GIF", ascii_frames.len());
+    encoder.into_inner()
+        .map_err(|e| MonochoraError::GifDecode(format!("Failed to finish GIF: {}", e)))
+}
+
+// The settings a size-capped GIF was written with; fits is false when every reduction was used up.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GifSizeFit {
+    pub bytes: usize,
+    pub fits: bool,
+    pub attempts: usize,
+    pub font_size: f32,
+    pub dimensions: Option<(u32, u32)>,
+    pub palette_size: usize,
+    pub frame_count: usize,
+}
+
+// Re-encodes in memory, shrinking the palette first, then the rendering scale, then the frame count,
+// until the GIF fits in max_bytes. The last attempt is written even if it is still too large.
+pub fn ascii_frames_to_gif_within_size<P: AsRef<Path>>(
+    ascii_frames: &[Vec<String>],
+    frame_delays: &[u16],
+    loop_count: u16,
+    output_path: P,
+    options: &AsciiGifOutputOptions,
+    target_dimensions: Option<(u32, u32)>,
+    max_bytes: u64,
+) -> Result<GifSizeFit> {
+    if ascii_frames.is_empty() {
+        return Err(MonochoraError::Config("No ASCII frames to convert".to_string()));
+    }
+
+    let mut options = options.clone();
+    let base_font_size = options.font_size;
+    let uses_color_palette = options.colored || options.quantize == QuantizeMethod::MedianCut;
+    let mut frames = Cow::Borrowed(ascii_frames);
+    let normalized_delays = normalize_frame_delays(frame_delays);
+    let mut delays: Vec<u16> = (0..ascii_frames.len())
+        .map(|frame_idx| frame_delay_for(&normalized_delays, frame_idx))
+        .collect();
+    let mut scale = 1.0f32;
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let dimensions = target_dimensions.map(|(width, height)| (
+            ((width as f32 * scale).round() as u32).max(1),
+            ((height as f32 * scale).round() as u32).max(1),
+        ));
+        let encoded = ascii_frames_to_gif_bytes(&frames, &delays, loop_count, &options, dimensions)?;
+        let palette_size = if uses_color_palette { options.max_colors } else { options.gray_levels };
+        debug!(
+            "Size fit attempt {}: {} bytes (font {:.1}, palette {}, {} frames)",
+            attempt, encoded.len(), options.font_size, palette_size, frames.len()
+        );
+
+        let fits = encoded.len() as u64 <= max_bytes;
+        let exhausted = palette_size <= MIN_SIZE_FIT_PALETTE && scale <= MIN_SIZE_FIT_SCALE && frames.len() == 1;
+        if fits || exhausted || attempt == MAX_SIZE_FIT_ATTEMPTS {
+            if !fits {
+                warn!(
+                    "Could not fit the GIF in {} bytes after {} attempts; writing the smallest result ({} bytes)",
+                    max_bytes, attempt, encoded.len()
+                );
+            }
+            write_output_file(output_path.as_ref(), &encoded)?;
+            return Ok(GifSizeFit {
+                bytes: encoded.len(),
+                fits,
+                attempts: attempt,
+                font_size: options.font_size,
+                dimensions,
+                palette_size,
+                frame_count: frames.len(),
+            });
+        }
+
+        if palette_size > MIN_SIZE_FIT_PALETTE {
+            let reduced = (palette_size / 2).max(MIN_SIZE_FIT_PALETTE);
+            if uses_color_palette {
+                options.max_colors = reduced;
+            } else {
+                options.gray_levels = reduced;
+            }
+        } else if scale > MIN_SIZE_FIT_SCALE {
+            // Pixel count, and roughly file size, goes with the square of the scale.
+            let ratio = (max_bytes as f32 / encoded.len() as f32).sqrt().clamp(0.5, 0.9);
+            scale = (scale * ratio).max(MIN_SIZE_FIT_SCALE);
+            options.font_size = base_font_size * scale;
+        } else {
+            // Drop every other frame, folding its delay into the frame before it.
+            let (kept_frames, kept_delays) = frames
+                .chunks(2)
+                .zip(delays.chunks(2))
+                .map(|(pair, pair_delays)| (pair[0].clone(), pair_delays.iter().fold(0u16, |sum, &delay| sum.saturating_add(delay))))
+                .unzip();
+            frames = Cow::Owned(kept_frames);
+            delays = kept_delays;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_character_count_skips_coalesced_color_runs() {
+        assert_eq!(calculate_line_character_count("\x1b[38;2;200;10;10mmmmm\x1b[38;2;0;0;0m  \x1b[0m"), 6);
+        assert_eq!(calculate_line_character_count("plain"), 5);
+    }
+
+    #[test]
+    fn line_character_count_ends_escapes_at_any_final_byte() {
+        // "~" and "@" are CSI final bytes too, not characters of the line.
+        assert_eq!(calculate_line_character_count("\x1b[2~ab\x1b[4@c"), 3);
+    }
+
+    #[test]
+    fn styled_cells_keep_the_run_color_for_every_character() {
+        let cells = styled_cells("\x1b[31mab\x1b[0mc");
+        assert_eq!(cells, vec![
+            ("\x1b[31m".to_string(), 'a'),
+            ("\x1b[31m".to_string(), 'b'),
+            (String::new(), 'c'),
+        ]);
+    }
+
+    // Groups of consecutive rows holding any non-background pixel, i.e. the text lines that were drawn.
+    fn drawn_line_bands(image: &RgbImage, bg_color: Rgb<u8>) -> usize {
+        let mut bands = 0;
+        let mut in_band = false;
+        for y in 0..image.height() {
+            let lit = (0..image.width()).any(|x| *image.get_pixel(x, y) != bg_color);
+            if lit && !in_band {
+                bands += 1;
+            }
+            in_band = lit;
+        }
+        bands
+    }
+
+    #[test]
+    fn frames_sized_to_just_fit_their_lines_draw_the_last_line() {
+        let options = AsciiGifOutputOptions::default();
+        let fonts = FontChain::load(&[], None).unwrap();
+        let scale = Scale::uniform(options.font_size);
+        let (line_height, _) = fonts.line_metrics(scale, options.line_height_multiplier);
+
+        for line_count in [1, 3, 8] {
+            let frame = vec!["HHHH".to_string(); line_count];
+            let height = (line_count as f32 * line_height).ceil() as u32;
+            let image = render_ascii_to_image(&frame, 40, height, scale, &fonts, &options).unwrap();
+            assert_eq!(drawn_line_bands(&image, options.bg_color), line_count, "{} lines in {}px", line_count, height);
+        }
+    }
+
+    #[test]
+    fn colored_frames_sized_to_just_fit_their_lines_draw_the_last_line() {
+        let options = AsciiGifOutputOptions { colored: true, ..AsciiGifOutputOptions::default() };
+        let fonts = FontChain::load(&[], None).unwrap();
+        let scale = Scale::uniform(options.font_size);
+        let (line_height, _) = fonts.line_metrics(scale, options.line_height_multiplier);
+
+        let frame = vec!["\x1b[38;2;255;0;0mHHHH\x1b[0m".to_string(); 4];
+        let height = (4.0 * line_height).ceil() as u32;
+        let image = render_ascii_to_image(&frame, 40, height, scale, &fonts, &options).unwrap();
+        assert_eq!(drawn_line_bands(&image, options.bg_color), 4);
+    }
+
+    #[test]
+    fn line_metrics_follow_the_font_and_fit_every_line() {
+        let fonts = FontChain::load(&[], None).unwrap();
+        let scale = Scale::uniform(14.0);
+        let v_metrics = fonts.fonts[0].v_metrics(scale);
+        let glyph_height = v_metrics.ascent - v_metrics.descent;
+
+        let (line_height, offset) = fonts.line_metrics(scale, 1.0);
+        assert_eq!(line_height, glyph_height + v_metrics.line_gap);
+
+        // Extra leading is split evenly above and below the glyph box.
+        let (spaced_height, spaced_offset) = fonts.line_metrics(scale, 1.5);
+        assert_eq!(spaced_height, line_height * 1.5);
+        assert_eq!(spaced_offset, offset + (spaced_height - line_height) / 2.0);
+
+        let options = AsciiGifOutputOptions { line_height_multiplier: 1.5, ..AsciiGifOutputOptions::default() };
+        let frame = vec!["HH".to_string(); 6];
+        let height = (6.0 * spaced_height).ceil() as u32;
+        let image = render_ascii_to_image(&frame, 20, height, scale, &fonts, &options).unwrap();
+        assert_eq!(drawn_line_bands(&image, options.bg_color), 6);
+    }
+
+    // A 16x8 GIF whose left half is the transparent palette index and right half opaque white.
+    fn half_transparent_gif() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut bytes, 16, 8, &[0, 0, 0, 255, 255, 255]).unwrap();
+            let pixels: Vec<u8> = (0..16 * 8).map(|idx| u8::from(idx % 16 >= 8)).collect();
+            encoder.write_frame(&gif::Frame::from_indexed_pixels(16, 8, &pixels, Some(0))).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn transparent_source_cells_stay_transparent_in_gif_output() {
+        let source = crate::handler::decode_gif_from_bytes(&half_transparent_gif()).unwrap();
+        let config = crate::converter::AsciiConverterConfig {
+            width: Some(8),
+            height: Some(2),
+            mark_transparent: true,
+            ..crate::converter::AsciiConverterConfig::default()
+        };
+        let frame = crate::converter::image_to_ascii(&source.frames[0].image, &config).unwrap();
+        assert!(frame.iter().all(|line| line.starts_with(crate::converter::TRANSPARENT_CELL)), "{:?}", frame);
+
+        let options = AsciiGifOutputOptions { transparent: true, ..AsciiGifOutputOptions::default() };
+        let bytes = ascii_frames_to_gif_bytes(&[frame], &[100], 0, &options, None).unwrap();
+
+        let mut decode_options = gif::DecodeOptions::new();
+        decode_options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = decode_options.read_info(bytes.as_slice()).unwrap();
+        let width = decoder.width() as usize;
+        let output = decoder.read_next_frame().unwrap().unwrap();
+        let transparent = output.transparent.expect("output frame has no transparent index");
+
+        // Only rows covered by the two text lines hold cells; the canvas below them is plain background.
+        let (_, line_height) = rendered_cell_size(&options).unwrap();
+        let cell_rows = (2.0 * line_height) as usize;
+        let rows: Vec<&[u8]> = output.buffer.chunks(width).take(cell_rows).collect();
+        assert!(rows.iter().all(|row| row[0] == transparent));
+        assert!(rows.iter().any(|row| row[width / 2..].iter().any(|&index| index != transparent)));
+    }
+
+    #[test]
+    fn rtl_layout_mirrors_each_line_and_right_aligns_short_ones() {
+        let frames = vec![vec!["abc".to_string(), "de".to_string()]];
+        assert_eq!(layout_frame_lines(&frames, TextLayout::Rtl), vec![vec!["cba".to_string(), " ed".to_string()]]);
+    }
+
+    #[test]
+    fn rtl_layout_keeps_each_character_in_its_color() {
+        let frames = vec![vec!["\x1b[31mab\x1b[0mc".to_string()]];
+        assert_eq!(layout_frame_lines(&frames, TextLayout::Rtl), vec![vec!["c\x1b[31mba\x1b[0m".to_string()]]);
+    }
+
+    // A bucket's center is at most half a bucket away from any color in it on each channel, so the
+    // table's pick is never more than twice that distance worse than the exact nearest color.
+    const COLOR_LUT_TOLERANCE: f32 = 2.0 * 1.732_051 * (1 << (COLOR_LUT_SHIFT - 1)) as f32;
+
+    #[test]
+    fn color_lookup_table_stays_within_tolerance_of_the_exact_search() {
+        // A fixed pseudo-random palette, so the test is repeatable without pulling in a RNG.
+        let mut state = 0x2545_f491u32;
+        let palette: Vec<u8> = (0..MAX_PALETTE_COLORS * 3)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 24) as u8
+            })
+            .collect();
+        let cache = create_color_cache_with_lut(&palette);
+
+        for r in (0..=255u8).step_by(5) {
+            for g in (0..=255u8).step_by(5) {
+                for b in (0..=255u8).step_by(5) {
+                    let rgb = [r, g, b];
+                    let picked = (palette_distance(rgb, &palette, find_closest_color(rgb, &palette, &cache) as usize) as f32).sqrt();
+                    let exact = (palette_distance(rgb, &palette, nearest_palette_index(rgb, &palette) as usize) as f32).sqrt();
+                    assert!(picked <= exact + COLOR_LUT_TOLERANCE, "{:?}: {} vs {}", rgb, picked, exact);
+                }
+            }
+        }
+
+        let first = [palette[0], palette[1], palette[2]];
+        assert_eq!(find_closest_color(first, &palette, &cache), cache.exact[&first]);
+    }
+
+    // Distinct shades strictly between the background and the text color, i.e. anti-aliased edge pixels.
+    fn edge_shades(image: &RgbImage) -> usize {
+        image.pixels()
+            .map(|pixel| pixel[0])
+            .filter(|&value| value != 0 && value != 255)
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    }
+
+    #[test]
+    fn seeded_jitter_and_median_cut_encode_identically() {
+        use crate::converter::{image_to_colored_ascii, AsciiConverterConfig};
+
+        let sources: Vec<image::RgbaImage> = (0..2u32)
+            .map(|shift| image::ImageBuffer::from_fn(48, 32, |x, y| {
+                // Neighboring pixels differ sharply, so moving a sample by a pixel changes the cell.
+                let noise = ((x * 31) ^ (y * 17) ^ (shift * 97)).wrapping_mul(2_654_435_761) >> 24;
+                image::Rgba([noise as u8, ((noise >> 2) as u8) ^ ((x * 8) as u8), (y * 8) as u8, 255])
+            }))
+            .collect();
+        let encode = |sample_seed: u64| {
+            let config = AsciiConverterConfig { width: Some(24), sample_jitter: 0.8, sample_seed, ..AsciiConverterConfig::default() };
+            let frames: Vec<Vec<String>> = sources.iter().map(|image| image_to_colored_ascii(image, &config).unwrap()).collect();
+            let options = AsciiGifOutputOptions {
+                colored: true,
+                quantize: QuantizeMethod::MedianCut,
+                max_colors: 32,
+                ..AsciiGifOutputOptions::default()
+            };
+            let bytes = ascii_frames_to_gif_bytes(&frames, &[100, 100], 0, &options, None).unwrap();
+            (frames, bytes)
+        };
+
+        let (frames, bytes) = encode(7);
+        assert_eq!(encode(7), (frames.clone(), bytes));
+        assert_ne!(encode(8).0, frames);
+    }
+
+    #[test]
+    fn supersampling_smooths_glyph_edges() {
+        let fonts = FontChain::load(&[], None).unwrap();
+        let scale = Scale::uniform(14.0);
+        let frame = vec!["@#/\\".to_string(), "AvXo".to_string()];
+
+        let render = |supersample: u32| {
+            let options = AsciiGifOutputOptions { supersample, ..AsciiGifOutputOptions::default() };
+            render_ascii_to_image_supersampled(&frame, 40, 30, scale, &fonts, &options).unwrap()
+        };
+        let (plain, smoothed) = (render(1), render(4));
+
+        assert_eq!(plain.dimensions(), smoothed.dimensions());
+        assert!(
+            edge_shades(&smoothed) > edge_shades(&plain),
+            "{} shades supersampled vs {} plain", edge_shades(&smoothed), edge_shades(&plain)
+        );
+    }
+
+    #[test]
+    fn supersample_factor_is_bounded() {
+        for supersample in [0, MAX_SUPERSAMPLE + 1] {
+            let options = AsciiGifOutputOptions { supersample, ..AsciiGifOutputOptions::default() };
+            assert!(options.validate().is_err(), "factor {}", supersample);
+        }
+        for supersample in 1..=MAX_SUPERSAMPLE {
+            assert!(AsciiGifOutputOptions { supersample, ..AsciiGifOutputOptions::default() }.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn oversized_supersampled_frames_are_refused_before_rendering() {
+        let options = AsciiGifOutputOptions { supersample: MAX_SUPERSAMPLE, ..AsciiGifOutputOptions::default() };
+        let frames = vec![vec!["@@".to_string()]];
+        match ascii_frames_to_gif_bytes(&frames, &[100], 0, &options, Some((2048, 2048))) {
+            Err(MonochoraError::Config(message)) => {
+                assert!(message.contains(&format!("maximum {}", MAX_SUPERSAMPLED_PIXELS)), "{}", message);
+            }
+            other => panic!("expected the supersampled pixel limit, got {:?}", other.map(|bytes| bytes.len())),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn output_to_a_named_pipe_is_written_straight_through() {
+        use std::os::unix::fs::FileTypeExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("out.gif");
+        let status = std::process::Command::new("mkfifo").arg(&fifo).status().unwrap();
+        assert!(status.success());
+
+        let reader_path = fifo.clone();
+        let reader = std::thread::spawn(move || std::fs::read(reader_path).unwrap());
+        write_output_file(&fifo, b"GIF89a-bytes").unwrap();
+
+        assert_eq!(reader.join().unwrap(), b"GIF89a-bytes");
+        assert!(std::fs::metadata(&fifo).unwrap().file_type().is_fifo());
+        assert!(!dir.path().join("out.gif.part").exists());
+    }
 }
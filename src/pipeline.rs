@@ -0,0 +1,150 @@
+use crate::converter::{image_to_ascii, image_to_colored_ascii, AsciiConverterConfig};
+use crate::handler::{adjust_frame_delays, decode_gif_from_bytes_with_limits, decode_gif_with_limits, DecodeLimits, GifData};
+use crate::web::get_input_path;
+use crate::{MonochoraError, Result};
+use rayon::prelude::*;
+use std::path::PathBuf;
+use tracing::info;
+
+// The embedding API: `monochora::convert_animation` decodes an input, converts every frame and applies
+// speed/fps, the same steps (and the same convert_frames) the CLI runs when no frame-editing flags are
+// given. Showing or writing the result is left to the display and output modules.
+
+#[derive(Debug, Clone)]
+pub enum InputSource {
+    Path(PathBuf),
+    Url(String),
+    Bytes(Vec<u8>),
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlaybackOptions {
+    pub speed: Option<f32>,
+    pub fps: Option<f32>,
+    pub loop_count: Option<u16>,
+    pub clear_on_exit: bool,
+    pub clear_between_frames: bool,
+    pub max_fps: Option<f32>,
+    pub log_dropped_frames: bool,
+}
+
+impl Default for PlaybackOptions {
+    fn default() -> Self {
+        Self {
+            speed: None,
+            fps: None,
+            loop_count: None,
+            clear_on_exit: true,
+            clear_between_frames: true,
+            max_fps: None,
+            log_dropped_frames: false,
+        }
+    }
+}
+
+impl PlaybackOptions {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(speed) = self.speed
+            && (speed <= 0.0 || !speed.is_finite()) {
+            return Err(MonochoraError::Config(format!("Invalid speed multiplier: {}", speed)));
+        }
+
+        if let Some(fps) = self.fps
+            && (fps <= 0.0 || !fps.is_finite()) {
+            return Err(MonochoraError::Config(format!("Invalid FPS value: {}", fps)));
+        }
+
+        if self.speed.is_some() && self.fps.is_some() {
+            return Err(MonochoraError::Config("Speed and FPS cannot both be set".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct MonochoraOptions {
+    pub input: InputSource,
+    pub converter: AsciiConverterConfig,
+    pub colored: bool,
+    pub limits: DecodeLimits,
+    pub playback: PlaybackOptions,
+    // None lets rayon pick the thread count; either way a local pool is used, never the global one.
+    pub threads: Option<usize>,
+}
+
+impl MonochoraOptions {
+    pub fn new(input: InputSource) -> Self {
+        Self {
+            input,
+            converter: AsciiConverterConfig::default(),
+            colored: false,
+            limits: DecodeLimits::default(),
+            playback: PlaybackOptions::default(),
+            threads: None,
+        }
+    }
+}
+
+// The converted animation, with delays already adjusted for speed/fps.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct AsciiAnimation {
+    pub frames: Vec<Vec<String>>,
+    pub delays: Vec<u16>,
+    pub loop_count: u16,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Converts every frame in parallel, in frame order; the CLI converts through this too.
+pub fn convert_frames(gif_data: &GifData, config: &AsciiConverterConfig, colored: bool) -> Result<Vec<Vec<String>>> {
+    gif_data.frames
+        .par_iter()
+        .map(|frame| {
+            if colored {
+                image_to_colored_ascii(&frame.image, config)
+            } else {
+                image_to_ascii(&frame.image, config)
+            }
+        })
+        .collect()
+}
+
+pub async fn convert_animation(options: &MonochoraOptions) -> Result<AsciiAnimation> {
+    options.converter.validate()?;
+    options.limits.validate()?;
+    options.playback.validate()?;
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.threads.unwrap_or(0))
+        .build()
+        .map_err(|e| MonochoraError::ThreadPool(e.to_string()))?;
+
+    let gif_data = match &options.input {
+        InputSource::Path(path) => pool.install(|| decode_gif_with_limits(path, &options.limits))?,
+        InputSource::Url(url) => {
+            // The download is deleted when input_path drops, so it has to outlive the decode.
+            let input_path = get_input_path(url).await?;
+            pool.install(|| decode_gif_with_limits(&input_path, &options.limits))?
+        }
+        InputSource::Bytes(bytes) => pool.install(|| decode_gif_from_bytes_with_limits(bytes, &options.limits))?,
+    };
+
+    let frames = pool.install(|| convert_frames(&gif_data, &options.converter, options.colored))?;
+    let original_delays: Vec<u16> = gif_data.frames.iter().map(|frame| frame.delay_time_ms).collect();
+    let delays = adjust_frame_delays(&original_delays, options.playback.speed, options.playback.fps);
+
+    let animation = AsciiAnimation {
+        frames,
+        delays,
+        loop_count: options.playback.loop_count.unwrap_or(gif_data.loop_count),
+        width: gif_data.width,
+        height: gif_data.height,
+    };
+
+    info!("Converted {} frames ({}x{})", animation.frames.len(), animation.width, animation.height);
+    Ok(animation)
+}
@@ -0,0 +1,194 @@
+use crate::{MonochoraError, Result};
+
+// Format: a header line, then the text with every run of 3+ identical characters written as the
+// character followed by its decimal count ("@12" is twelve '@'). Digits and '\' in the text are
+// escaped with a leading '\' so a bare digit is always a count. Newlines are never counted, and
+// CSI escape sequences ("\x1b[...m") are copied verbatim so colored lines keep their escapes.
+pub const RLE_HEADER: &str = "MONOCHORA-RLE 1\n";
+
+const MIN_RUN_LENGTH: usize = 3;
+const MAX_RUN_LENGTH: usize = 1 << 20;
+
+fn is_csi_final(c: char) -> bool {
+    ('@'..='~').contains(&c)
+}
+
+pub fn encode_rle_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut encoded = String::with_capacity(RLE_HEADER.len() + text.len() / 2);
+    encoded.push_str(RLE_HEADER);
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\x1b' && chars.get(i + 1) == Some(&'[') {
+            encoded.push_str("\x1b[");
+            i += 2;
+            while i < chars.len() && chars[i] != '\n' {
+                encoded.push(chars[i]);
+                i += 1;
+                if is_csi_final(chars[i - 1]) {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        if c == '\n' || c == '\x1b' {
+            encoded.push(c);
+            i += 1;
+            continue;
+        }
+
+        let run = chars[i..].iter().take_while(|&&next| next == c).count();
+        let push_literal = |encoded: &mut String| {
+            if c.is_ascii_digit() || c == '\\' {
+                encoded.push('\\');
+            }
+            encoded.push(c);
+        };
+
+        // Runs longer than the decoder accepts are split into several counted runs.
+        let mut remaining = run;
+        while remaining > 0 {
+            let chunk = remaining.min(MAX_RUN_LENGTH);
+            if chunk >= MIN_RUN_LENGTH {
+                push_literal(&mut encoded);
+                encoded.push_str(&chunk.to_string());
+            } else {
+                for _ in 0..chunk {
+                    push_literal(&mut encoded);
+                }
+            }
+            remaining -= chunk;
+        }
+        i += run;
+    }
+
+    encoded
+}
+
+pub fn decode_rle_text(encoded: &str) -> Result<String> {
+    let body = encoded.strip_prefix(RLE_HEADER).ok_or_else(|| {
+        MonochoraError::Config("Not run-length encoded text (missing MONOCHORA-RLE header)".to_string())
+    })?;
+
+    let mut decoded = String::with_capacity(body.len() * 2);
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let literal = match c {
+            '\n' => {
+                decoded.push('\n');
+                continue;
+            }
+            '\x1b' if chars.peek() == Some(&'[') => {
+                decoded.push('\x1b');
+                decoded.push(chars.next().unwrap_or('['));
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    decoded.push(next);
+                    chars.next();
+                    if is_csi_final(next) {
+                        break;
+                    }
+                }
+                continue;
+            }
+            '\\' => chars.next().ok_or_else(|| {
+                MonochoraError::Config("Run-length text ends with a dangling '\\'".to_string())
+            })?,
+            digit if digit.is_ascii_digit() => {
+                return Err(MonochoraError::Config(format!(
+                    "Unexpected run count '{}' with no preceding character", digit
+                )));
+            }
+            other => other,
+        };
+
+        let mut count_digits = String::new();
+        while let Some(&next) = chars.peek() {
+            if !next.is_ascii_digit() {
+                break;
+            }
+            count_digits.push(next);
+            chars.next();
+        }
+
+        let count = if count_digits.is_empty() {
+            1
+        } else {
+            match count_digits.parse::<usize>() {
+                Ok(count) if (1..=MAX_RUN_LENGTH).contains(&count) => count,
+                _ => {
+                    return Err(MonochoraError::Config(format!(
+                        "Invalid run count '{}' (must be between 1 and {})", count_digits, MAX_RUN_LENGTH
+                    )));
+                }
+            }
+        };
+
+        for _ in 0..count {
+            decoded.push(literal);
+        }
+    }
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(text: &str) {
+        assert_eq!(decode_rle_text(&encode_rle_text(text)).unwrap(), text);
+    }
+
+    #[test]
+    fn plain_colored_and_multi_frame_text_round_trips() {
+        assert_round_trip("@@@@@@@@....  ..\n####    ####\n");
+        assert_round_trip("\x1b[38;2;10;20;30m@@@@@@\x1b[0m   \x1b[31mmmmm\x1b[0m\n");
+        assert_round_trip("====\nFrame 1 (delay: 100ms)\n====\n@@@@\n\n====\nFrame 2 (delay: 40ms)\n====\n    \n");
+        assert_round_trip("");
+    }
+
+    #[test]
+    fn only_runs_of_the_minimum_length_are_counted() {
+        let short = "@".repeat(MIN_RUN_LENGTH - 1);
+        assert_eq!(encode_rle_text(&short), format!("{}{}", RLE_HEADER, short));
+
+        let counted = "@".repeat(MIN_RUN_LENGTH);
+        assert_eq!(encode_rle_text(&counted), format!("{}@{}", RLE_HEADER, MIN_RUN_LENGTH));
+        assert_round_trip(&short);
+        assert_round_trip(&counted);
+    }
+
+    #[test]
+    fn runs_past_the_maximum_are_split() {
+        for run in [MAX_RUN_LENGTH, MAX_RUN_LENGTH + 1, MAX_RUN_LENGTH + MIN_RUN_LENGTH, 2 * MAX_RUN_LENGTH + 7] {
+            assert_round_trip(&" ".repeat(run));
+        }
+    }
+
+    #[test]
+    fn digits_and_backslashes_are_escaped() {
+        let text = "1112223\\\\ab\\9";
+        let encoded = encode_rle_text(text);
+        assert!(encoded.starts_with(&format!("{}\\13\\23\\3", RLE_HEADER)), "{:?}", encoded);
+        assert_round_trip(text);
+        assert_round_trip("0123456789\n\\");
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert!(decode_rle_text("@5").is_err());
+        assert!(decode_rle_text("MONOCHORA-RLE 2\n@5").is_err());
+        assert!(decode_rle_text(&format!("{}ab\\", RLE_HEADER)).is_err());
+        assert!(decode_rle_text(&format!("{}5@", RLE_HEADER)).is_err());
+        assert!(decode_rle_text(&format!("{}@0", RLE_HEADER)).is_err());
+        assert!(decode_rle_text(&format!("{}@{}", RLE_HEADER, MAX_RUN_LENGTH + 1)).is_err());
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{MonochoraError, Result};
 use crate::converter::{image_to_ascii, image_to_colored_ascii, AsciiConverterConfig};
-use crate::handler::GifData;
+use crate::handler::{normalize_frame_delays, GifData, DEFAULT_FRAME_DELAY_MS};
 use crossterm::terminal::size;
-use std::sync::mpsc::{self, Sender};
-use std::thread;
-use std::time::Duration;
+use rayon::ThreadPool;
+use std::sync::Arc;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use tokio::sync::watch;
 use tracing::{debug, warn};
 
@@ -26,69 +28,85 @@ impl TerminalDimensions {
     }
 }
 
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DROP_JOIN_TIMEOUT: Duration = Duration::from_millis(500);
+
 pub struct TerminalWatcher {
     dimensions_tx: watch::Sender<TerminalDimensions>,
     dimensions_rx: watch::Receiver<TerminalDimensions>,
     stop_tx: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
 }
 
 impl TerminalWatcher {
     pub fn new() -> Result<Self> {
-        let initial_dims = TerminalDimensions::current()?;
+        Ok(Self::with_dimensions(TerminalDimensions::current()?))
+    }
+
+    fn with_dimensions(initial_dims: TerminalDimensions) -> Self {
         let (dimensions_tx, dimensions_rx) = watch::channel(initial_dims);
         
-        Ok(Self {
+        Self {
             dimensions_tx,
             dimensions_rx,
             stop_tx: None,
-        })
+            handle: None,
+        }
     }
 
     pub fn start_watching(&mut self) -> Result<()> {
+        self.stop();
+
         let (stop_tx, stop_rx) = mpsc::channel();
         let tx = self.dimensions_tx.clone();
         
-        thread::spawn(move || {
-            let mut last_dimensions = match TerminalDimensions::current() {
-                Ok(dims) => dims,
-                Err(_) => return,
-            };
-
-            loop {
-                if stop_rx.try_recv().is_ok() {
-                    debug!("Terminal watcher stopping");
-                    break;
-                }
+        let handle = thread::Builder::new()
+            .name("terminal-watcher".to_string())
+            .spawn(move || {
+                let mut last_dimensions = match TerminalDimensions::current() {
+                    Ok(dims) => dims,
+                    Err(_) => return,
+                };
+
+                loop {
+                    // Waiting on the stop channel doubles as the poll delay, so stop() is seen immediately.
+                    match stop_rx.recv_timeout(POLL_INTERVAL) {
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Ok(()) | Err(RecvTimeoutError::Disconnected) => {
+                            debug!("Terminal watcher stopping");
+                            break;
+                        }
+                    }
 
-                match TerminalDimensions::current() {
-                    Ok(current_dims) => {
-                        if current_dims != last_dimensions {
-                            debug!(
-                                "Terminal resize detected: {}x{} -> {}x{}",
-                                last_dimensions.width,
-                                last_dimensions.height,
-                                current_dims.width,
-                                current_dims.height
-                            );
+                    match TerminalDimensions::current() {
+                        Ok(current_dims) => {
+                            if current_dims != last_dimensions {
+                                debug!(
+                                    "Terminal resize detected: {}x{} -> {}x{}",
+                                    last_dimensions.width,
+                                    last_dimensions.height,
+                                    current_dims.width,
+                                    current_dims.height
+                                );
                             
-                            if let Err(e) = tx.send(current_dims) {
-                                warn!("Failed to send dimension update: {}", e);
-                                break;
-                            }
+                                if let Err(e) = tx.send(current_dims) {
+                                    warn!("Failed to send dimension update: {}", e);
+                                    break;
+                                }
                             
-                            last_dimensions = current_dims;
+                                last_dimensions = current_dims;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Failed to get terminal dimensions: {}", e);
                         }
-                    }
-                    Err(e) => {
-                        warn!("Failed to get terminal dimensions: {}", e);
                     }
                 }
-
-                thread::sleep(Duration::from_millis(100));
-            }
-        });
+            })
+            .map_err(|e| MonochoraError::Terminal(format!("Failed to spawn terminal watcher: {}", e)))?;
 
         self.stop_tx = Some(stop_tx);
+        self.handle = Some(handle);
         Ok(())
     }
 
@@ -101,6 +119,15 @@ impl TerminalWatcher {
     }
 
     pub fn stop(&mut self) {
+        self.signal_stop();
+
+        if let Some(handle) = self.handle.take()
+            && handle.join().is_err() {
+            warn!("Terminal watcher thread panicked");
+        }
+    }
+
+    fn signal_stop(&mut self) {
         if let Some(stop_tx) = self.stop_tx.take() {
             let _ = stop_tx.send(());
         }
@@ -109,8 +136,33 @@ impl TerminalWatcher {
 
 impl Drop for TerminalWatcher {
     fn drop(&mut self) {
-        self.stop();
+        self.signal_stop();
+
+        // Don't let a stuck terminal query hang shutdown; give the thread a moment, then detach it.
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+
+        let deadline = Instant::now() + DROP_JOIN_TIMEOUT;
+        while !handle.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        if handle.is_finished() {
+            let _ = handle.join();
+        } else {
+            debug!("Terminal watcher thread did not stop in time, detaching");
+        }
+    }
+}
+
+fn align_frame_delays(mut frame_delays: Vec<u16>, frame_count: usize) -> Vec<u16> {
+    if frame_delays.len() != frame_count {
+        debug!("Aligning {} frame delays to {} frames", frame_delays.len(), frame_count);
+        let fill = frame_delays.last().copied().unwrap_or(DEFAULT_FRAME_DELAY_MS);
+        frame_delays.resize(frame_count, fill);
     }
+    frame_delays
 }
 
 pub struct ResponsiveFrameManager {
@@ -120,6 +172,7 @@ pub struct ResponsiveFrameManager {
     current_dimensions: TerminalDimensions,
     cached_frames: Option<Vec<Vec<String>>>,
     colored: bool,
+    thread_pool: Option<Arc<ThreadPool>>,
 }
 
 impl ResponsiveFrameManager {
@@ -130,6 +183,9 @@ impl ResponsiveFrameManager {
         initial_dimensions: TerminalDimensions,
         colored: bool,
     ) -> Self {
+        let frame_delays = normalize_frame_delays(&frame_delays).into_owned();
+        let frame_delays = align_frame_delays(frame_delays, gif_data.frames.len());
+
         Self {
             gif_data,
             config_template,
@@ -137,9 +193,15 @@ impl ResponsiveFrameManager {
             current_dimensions: initial_dimensions,
             cached_frames: None,
             colored,
+            thread_pool: None,
         }
     }
 
+    pub fn with_thread_pool(mut self, thread_pool: Arc<ThreadPool>) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
     pub fn update_dimensions(&mut self, new_dimensions: TerminalDimensions) -> bool {
         if new_dimensions != self.current_dimensions {
             self.current_dimensions = new_dimensions;
@@ -173,16 +235,23 @@ impl ResponsiveFrameManager {
         config.width = Some(target_width);
         config.height = Some(target_height);
 
-        let new_frames: Result<Vec<Vec<String>>> = self.gif_data.frames
-            .iter()
-            .map(|frame| {
-                if self.colored {
-                    image_to_colored_ascii(&frame.image, &config)
-                } else {
-                    image_to_ascii(&frame.image, &config)
-                }
-            })
-            .collect();
+        let convert = || -> Result<Vec<Vec<String>>> {
+            self.gif_data.frames
+                .iter()
+                .map(|frame| {
+                    if self.colored {
+                        image_to_colored_ascii(&frame.image, &config)
+                    } else {
+                        image_to_ascii(&frame.image, &config)
+                    }
+                })
+                .collect()
+        };
+
+        let new_frames = match &self.thread_pool {
+            Some(pool) => pool.install(convert),
+            None => convert(),
+        };
 
         self.cached_frames = Some(new_frames?);
         Ok(())
@@ -225,3 +294,67 @@ impl ResponsiveFrameManager {
         resized_frame
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::GifFrame;
+    use image::{ImageBuffer, Rgba};
+
+    fn gif_with_frames(count: usize) -> GifData {
+        let frames = (0..count)
+            .map(|idx| GifFrame {
+                image: ImageBuffer::from_pixel(8, 8, Rgba([(idx * 60) as u8, 0, 0, 255])),
+                delay_time_ms: 100,
+            })
+            .collect();
+        GifData { frames, width: 8, height: 8, loop_count: 0 }
+    }
+
+    #[test]
+    fn short_delay_lists_are_padded_to_the_frame_count() {
+        let dimensions = TerminalDimensions::new(20, 12);
+        let mut manager = ResponsiveFrameManager::new(gif_with_frames(4), AsciiConverterConfig::default(), vec![40, 70], dimensions, false);
+
+        assert_eq!(manager.get_frame_delays(), &[40, 70, 70, 70]);
+        assert_eq!(manager.get_frames().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn missing_delays_fall_back_to_the_default() {
+        let dimensions = TerminalDimensions::new(20, 12);
+        let manager = ResponsiveFrameManager::new(gif_with_frames(3), AsciiConverterConfig::default(), Vec::new(), dimensions, false);
+
+        assert_eq!(manager.get_frame_delays(), &[DEFAULT_FRAME_DELAY_MS; 3]);
+    }
+
+    #[test]
+    fn watcher_starts_and_stops_cleanly() {
+        let mut watcher = TerminalWatcher::with_dimensions(TerminalDimensions::new(80, 24));
+
+        watcher.start_watching().unwrap();
+        assert!(watcher.handle.is_some() && watcher.stop_tx.is_some());
+
+        // Restarting replaces the running thread instead of leaking it.
+        watcher.start_watching().unwrap();
+
+        let stop_started = Instant::now();
+        watcher.stop();
+        assert!(watcher.handle.is_none() && watcher.stop_tx.is_none());
+        assert!(stop_started.elapsed() < DROP_JOIN_TIMEOUT);
+
+        // Stopping twice is harmless, and the last known size is kept.
+        watcher.stop();
+        assert_eq!(watcher.current_dimensions(), TerminalDimensions::new(80, 24));
+    }
+
+    #[test]
+    fn dropping_a_running_watcher_joins_its_thread() {
+        let mut watcher = TerminalWatcher::with_dimensions(TerminalDimensions::new(80, 24));
+        watcher.start_watching().unwrap();
+
+        let drop_started = Instant::now();
+        drop(watcher);
+        assert!(drop_started.elapsed() < DROP_JOIN_TIMEOUT);
+    }
+}
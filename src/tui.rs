@@ -0,0 +1,210 @@
+use crate::converter::{image_to_ascii, image_to_colored_ascii, AsciiConverterConfig};
+use crate::{MonochoraError, Result};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{read, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType},
+};
+use image::{ImageBuffer, Rgba};
+use std::io::{self, Write};
+
+const STATUS_LINES: u16 = 3;
+const MIN_WIDTH: u32 = 4;
+const BRIGHTNESS_STEP: f32 = 0.05;
+const CONTRAST_STEP: f32 = 0.1;
+const MIN_CONTRAST: f32 = 0.1;
+const MAX_CONTRAST: f32 = 5.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharsetChoice {
+    Custom,
+    Detailed,
+    Simple,
+}
+
+impl CharsetChoice {
+    fn label(self) -> &'static str {
+        match self {
+            CharsetChoice::Custom => "custom",
+            CharsetChoice::Detailed => "detailed",
+            CharsetChoice::Simple => "simple",
+        }
+    }
+}
+
+struct TweakerState {
+    config: AsciiConverterConfig,
+    custom_charset: Option<Vec<char>>,
+    charset_choice: CharsetChoice,
+}
+
+impl TweakerState {
+    fn new(mut config: AsciiConverterConfig, default_width: u32) -> Self {
+        let custom_charset = config.custom_charset.clone();
+        let charset_choice = if custom_charset.is_some() {
+            CharsetChoice::Custom
+        } else if config.detailed {
+            CharsetChoice::Detailed
+        } else {
+            CharsetChoice::Simple
+        };
+
+        config.width = Some(config.width.unwrap_or(default_width).max(MIN_WIDTH));
+        config.height = None;
+        config.scale_factor = None;
+        config.scale_x = None;
+        config.scale_y = None;
+
+        Self { config, custom_charset, charset_choice }
+    }
+
+    fn cycle_charset(&mut self) {
+        let choices: &[CharsetChoice] = if self.custom_charset.is_some() {
+            &[CharsetChoice::Custom, CharsetChoice::Detailed, CharsetChoice::Simple]
+        } else {
+            &[CharsetChoice::Detailed, CharsetChoice::Simple]
+        };
+
+        let current = choices.iter().position(|&c| c == self.charset_choice).unwrap_or(0);
+        self.charset_choice = choices[(current + 1) % choices.len()];
+
+        match self.charset_choice {
+            CharsetChoice::Custom => {
+                self.config.custom_charset = self.custom_charset.clone();
+            }
+            CharsetChoice::Detailed => {
+                self.config.custom_charset = None;
+                self.config.detailed = true;
+            }
+            CharsetChoice::Simple => {
+                self.config.custom_charset = None;
+                self.config.detailed = false;
+            }
+        }
+    }
+
+    fn adjust_width(&mut self, delta: i32) {
+        let width = self.config.width.unwrap_or(MIN_WIDTH) as i32 + delta;
+        self.config.width = Some(width.max(MIN_WIDTH as i32) as u32);
+    }
+
+    fn adjust_brightness(&mut self, delta: f32) {
+        self.config.brightness = (self.config.brightness + delta).clamp(-1.0, 1.0);
+    }
+
+    fn adjust_contrast(&mut self, delta: f32) {
+        self.config.contrast = (self.config.contrast + delta).clamp(MIN_CONTRAST, MAX_CONTRAST);
+    }
+
+    fn status_line(&self, frame_width: u32, frame_height: u32) -> String {
+        let (columns, rows) = self.config.target_dimensions(frame_width, frame_height).unwrap_or((0, 0));
+        format!(
+            "size: {}x{}  charset: {}  invert: {}  brightness: {:+.2}  contrast: {:.1}",
+            columns,
+            rows,
+            self.charset_choice.label(),
+            if self.config.invert { "on" } else { "off" },
+            self.config.brightness,
+            self.config.contrast,
+        )
+    }
+}
+
+fn render_preview(
+    stdout: &mut io::Stdout,
+    frame: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    state: &TweakerState,
+    colored: bool,
+) -> Result<()> {
+    let (_, rows) = size()
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to get terminal size: {}", e)))?;
+    let max_lines = rows.saturating_sub(STATUS_LINES) as usize;
+
+    let lines = if colored {
+        image_to_colored_ascii(frame, &state.config)?
+    } else {
+        image_to_ascii(frame, &state.config)?
+    };
+
+    execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to clear screen: {}", e)))?;
+
+    for line in lines.iter().take(max_lines) {
+        write!(stdout, "{}\r\n", line)?;
+    }
+
+    write!(stdout, "\r\n{}\r\n", state.status_line(frame.width(), frame.height()))?;
+    write!(
+        stdout,
+        "[←/→] width  [c] charset  [i] invert  [b/B] brightness  [[/]] contrast  [Enter] apply  [q/Esc] quit"
+    )?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+fn run_event_loop(
+    stdout: &mut io::Stdout,
+    frame: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    state: &mut TweakerState,
+    colored: bool,
+) -> Result<bool> {
+    render_preview(stdout, frame, state, colored)?;
+
+    loop {
+        let event = read()
+            .map_err(|e| MonochoraError::Terminal(format!("Failed to read terminal event: {}", e)))?;
+
+        let key = match event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            Event::Resize(_, _) => {
+                render_preview(stdout, frame, state, colored)?;
+                continue;
+            }
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Enter => return Ok(true),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(false),
+            KeyCode::Left => state.adjust_width(-1),
+            KeyCode::Right => state.adjust_width(1),
+            KeyCode::Char('c') | KeyCode::Char('C') => state.cycle_charset(),
+            KeyCode::Char('i') | KeyCode::Char('I') => state.config.invert = !state.config.invert,
+            KeyCode::Char('b') => state.adjust_brightness(-BRIGHTNESS_STEP),
+            KeyCode::Char('B') => state.adjust_brightness(BRIGHTNESS_STEP),
+            KeyCode::Char('[') => state.adjust_contrast(-CONTRAST_STEP),
+            KeyCode::Char(']') => state.adjust_contrast(CONTRAST_STEP),
+            _ => continue,
+        }
+
+        render_preview(stdout, frame, state, colored)?;
+    }
+}
+
+pub fn run_parameter_tweaker(
+    frame: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    config: AsciiConverterConfig,
+    colored: bool,
+) -> Result<Option<AsciiConverterConfig>> {
+    let (cols, _) = size()
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to get terminal size: {}", e)))?;
+    let mut state = TweakerState::new(config, (cols as u32).saturating_sub(2));
+
+    let mut stdout = io::stdout();
+    enable_raw_mode()
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to enable raw mode: {}", e)))?;
+    execute!(stdout, Hide)
+        .map_err(|e| MonochoraError::Terminal(format!("Failed to hide cursor: {}", e)))?;
+
+    let result = run_event_loop(&mut stdout, frame, &mut state, colored);
+
+    let _ = disable_raw_mode();
+    let _ = execute!(stdout, Show, Clear(ClearType::All), MoveTo(0, 0));
+
+    match result? {
+        true => Ok(Some(state.config)),
+        false => Ok(None),
+    }
+}
@@ -0,0 +1,55 @@
+use crate::converter::{image_to_ascii, image_to_colored_ascii, AsciiConverterConfig};
+use crate::handler::decode_gif_from_bytes;
+use crate::{MonochoraError, Result};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[derive(Serialize)]
+struct ConvertedGif {
+    frames: Vec<Vec<String>>,
+    delays: Vec<u16>,
+    width: u32,
+    height: u32,
+    loop_count: u16,
+}
+
+fn convert(bytes: &[u8], config: &AsciiConverterConfig, colored: bool) -> Result<ConvertedGif> {
+    config.validate()?;
+
+    let gif_data = decode_gif_from_bytes(bytes)?;
+
+    let frames = gif_data.frames
+        .iter()
+        .map(|frame| {
+            if colored {
+                image_to_colored_ascii(&frame.image, config)
+            } else {
+                image_to_ascii(&frame.image, config)
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ConvertedGif {
+        frames,
+        delays: gif_data.frames.iter().map(|frame| frame.delay_time_ms).collect(),
+        width: gif_data.width,
+        height: gif_data.height,
+        loop_count: gif_data.loop_count,
+    })
+}
+
+#[wasm_bindgen(js_name = convertGifBytes)]
+pub fn convert_gif_bytes(bytes: &[u8], config: JsValue, colored: bool) -> std::result::Result<JsValue, JsValue> {
+    let config: AsciiConverterConfig = if config.is_undefined() || config.is_null() {
+        AsciiConverterConfig::default()
+    } else {
+        serde_wasm_bindgen::from_value(config)
+            .map_err(|e| JsValue::from_str(&MonochoraError::Config(format!("Invalid converter config: {}", e)).to_string()))?
+    };
+
+    let converted = convert(bytes, &config, colored)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    serde_wasm_bindgen::to_value(&converted)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
@@ -1,13 +1,44 @@
 use crate::{MonochoraError, Result};
 use std::io::Write;
-use std::path::PathBuf;
-use tempfile::NamedTempFile;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use tempfile::{NamedTempFile, TempPath};
 use url::Url;
 use tracing::{debug, info, warn};
 
-pub async fn download_gif_from_url(url: &str) -> Result<PathBuf> {
+// A resolved input file. A download is deleted when this is dropped, so keep it alive until decoding is done.
+#[derive(Debug)]
+pub enum InputPath {
+    Local(PathBuf),
+    Downloaded(TempPath),
+}
+
+impl InputPath {
+    pub fn is_download(&self) -> bool {
+        matches!(self, Self::Downloaded(_))
+    }
+}
+
+impl Deref for InputPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        match self {
+            Self::Local(path) => path,
+            Self::Downloaded(temp_path) => temp_path,
+        }
+    }
+}
+
+impl AsRef<Path> for InputPath {
+    fn as_ref(&self) -> &Path {
+        self
+    }
+}
+
+pub async fn download_gif_from_url(url: &str) -> Result<TempPath> {
     let parsed_url = Url::parse(url)
-        .map_err(|e| MonochoraError::UrlParse(e))?;
+        .map_err(MonochoraError::UrlParse)?;
     
     match parsed_url.scheme() {
         "http" | "https" => {},
@@ -22,18 +53,17 @@ pub async fn download_gif_from_url(url: &str) -> Result<PathBuf> {
         .timeout(std::time::Duration::from_secs(30))
         .user_agent("monochora-gif-converter/1.0")
         .build()
-        .map_err(|e| MonochoraError::Http(e))?;
+        .map_err(MonochoraError::Http)?;
     
     let response = client
         .get(url)
         .send()
         .await
-        .map_err(|e| MonochoraError::Http(e))?;
+        .map_err(MonochoraError::Http)?;
     
     if !response.status().is_success() {
         return Err(MonochoraError::Io(
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
+            std::io::Error::other(
                 format!("HTTP request failed with status: {}", response.status())
             )
         ));
@@ -63,41 +93,33 @@ pub async fn download_gif_from_url(url: &str) -> Result<PathBuf> {
     let file_extension = get_file_extension_from_url(&parsed_url)
         .unwrap_or_else(|| "gif".to_string());
     
-    let mut temp_file = NamedTempFile::with_suffix(&format!(".{}", file_extension))
-        .map_err(|e| MonochoraError::Io(e))?;
+    let mut temp_file = NamedTempFile::with_suffix(format!(".{}", file_extension))
+        .map_err(MonochoraError::Io)?;
     
     let bytes = response.bytes().await
-        .map_err(|e| MonochoraError::Http(e))?;
+        .map_err(MonochoraError::Http)?;
     
      if bytes.is_empty() {
         return Err(MonochoraError::Io(
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
+            std::io::Error::other(
                 "Downloaded file is empty"
             )
         ));
     }
     
     temp_file.write_all(&bytes)
-        .map_err(|e| MonochoraError::Io(e))?;
+        .map_err(MonochoraError::Io)?;
     
     let temp_path = temp_file.into_temp_path();
-    let final_path = temp_path.keep()
-        .map_err(|e| MonochoraError::Io(
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Failed to persist temporary file: {}", e)
-            )
-        ))?;
     
-    info!("Downloaded successfully to temporary file: {}", final_path.display());
+    info!("Downloaded successfully to temporary file: {}", temp_path.display());
     
-    Ok(final_path)
+    Ok(temp_path)
 }
 
 fn get_file_extension_from_url(url: &Url) -> Option<String> {
-    let path_segments = url.path_segments()?;
-    let last_segment = path_segments.last()?;
+    let mut path_segments = url.path_segments()?;
+    let last_segment = path_segments.next_back()?;
     
     let dot_pos = last_segment.rfind('.')?;
     let extension = &last_segment[dot_pos + 1..];
@@ -114,9 +136,9 @@ pub fn is_url(input: &str) -> bool {
     input.starts_with("http://") || input.starts_with("https://")
 }
 
-pub async fn get_input_path(input: &str) -> Result<PathBuf> {
+pub async fn get_input_path(input: &str) -> Result<InputPath> {
     if is_url(input) {
-        download_gif_from_url(input).await
+        download_gif_from_url(input).await.map(InputPath::Downloaded)
     } else {
         let path = PathBuf::from(input);
         
@@ -139,6 +161,6 @@ pub async fn get_input_path(input: &str) -> Result<PathBuf> {
         }
         
         debug!("Using local file: {}", path.display());
-        Ok(path)
+        Ok(InputPath::Local(path))
     }
 }